@@ -0,0 +1,465 @@
+//! Builder of animated images (GIF, WebP, MNG, ...) out of single frames.
+//!
+//! ```no_run
+//! use graphicsmagick::{
+//!     animation::AnimationBuilder, initialize, types::DisposeType, wand::MagickWand,
+//! };
+//!
+//! initialize();
+//!
+//! let mut builder = AnimationBuilder::new().format("GIF");
+//! for path in ["frame0.png", "frame1.png", "frame2.png"] {
+//!     let mut mw = MagickWand::new();
+//!     mw.read_image(path).unwrap();
+//!     builder = builder.frame(mw, 10, DisposeType::BackgroundDispose);
+//! }
+//! let gif = builder.build().unwrap();
+//! std::fs::write("animation.gif", gif).unwrap();
+//! ```
+
+use crate::{types::DisposeType, wand::MagickWand};
+use graphicsmagick_sys::{MagickCoalesceImages, MagickDeconstructImages};
+use std::os::raw::c_ulong;
+use thiserror::Error as ThisError;
+
+/// Optimisation applied to the sequence before it is written, see
+/// [AnimationBuilder::optimization].
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum Optimization {
+    /// Write the frames as they are.
+    #[default]
+    None,
+    /// Composite every frame over the previous ones, see
+    /// [MagickWand::coalesce_images].
+    Coalesce,
+    /// Crop every frame to the region that differs from the previous one, see
+    /// [MagickWand::deconstruct_images].
+    Deconstruct,
+}
+
+/// Error returned by [AnimationBuilder::build] for invalid frames.
+#[derive(ThisError, Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ValidationError {
+    /// The animation has no frames.
+    #[error("animation has no frames")]
+    NoFrames,
+
+    /// A frame holds no image, or more than one.
+    #[error("frame #{index} holds {images} images instead of one")]
+    NotSingleImage { index: usize, images: c_ulong },
+
+    /// Without a canvas, a frame is not the same size as the first one.
+    #[error("frame #{index} is {width}x{height}, expected {expected_width}x{expected_height}")]
+    SizeMismatch {
+        index: usize,
+        width: c_ulong,
+        height: c_ulong,
+        expected_width: c_ulong,
+        expected_height: c_ulong,
+    },
+
+    /// A frame doesn't fit in the canvas.
+    #[error(
+        "frame #{index} is {width}x{height}, larger than the {canvas_width}x{canvas_height} canvas"
+    )]
+    LargerThanCanvas {
+        index: usize,
+        width: c_ulong,
+        height: c_ulong,
+        canvas_width: c_ulong,
+        canvas_height: c_ulong,
+    },
+}
+
+#[derive(Debug)]
+struct Frame<'a> {
+    wand: MagickWand<'a>,
+    delay: c_ulong,
+    dispose: DisposeType,
+}
+
+/// Builder of an animated image, in the order the frames are added.
+///
+/// Every frame is a wand holding a single image, placed at the top left
+/// corner of the canvas.
+#[derive(Debug)]
+pub struct AnimationBuilder<'a> {
+    frames: Vec<Frame<'a>>,
+    canvas: Option<(c_ulong, c_ulong)>,
+    #[cfg(feature = "v1_3_26")]
+    iterations: Option<c_ulong>,
+    optimization: Optimization,
+    format: String,
+}
+
+impl Default for AnimationBuilder<'_> {
+    fn default() -> Self {
+        AnimationBuilder {
+            frames: Vec::new(),
+            canvas: None,
+            #[cfg(feature = "v1_3_26")]
+            iterations: None,
+            optimization: Optimization::None,
+            format: "GIF".to_owned(),
+        }
+    }
+}
+
+impl<'a> AnimationBuilder<'a> {
+    /// Create a builder without frames, writing GIF.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Append a frame shown for `delay` ticks (1/100th of a second), then
+    /// disposed of with `dispose`.
+    pub fn frame(mut self, wand: MagickWand<'a>, delay: c_ulong, dispose: DisposeType) -> Self {
+        self.frames.push(Frame {
+            wand,
+            delay,
+            dispose,
+        });
+        self
+    }
+
+    /// Set the size of the canvas every frame must fit in.
+    ///
+    /// Without a canvas, every frame must be the size of the first one, which
+    /// is then the size of the canvas.
+    pub fn canvas(mut self, width: c_ulong, height: c_ulong) -> Self {
+        self.canvas = Some((width, height));
+        self
+    }
+
+    /// Set how many times the animation loops, `0` for forever.
+    ///
+    /// The loop count of the first frame is kept if not set.
+    #[cfg(feature = "v1_3_26")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "v1_3_26")))]
+    pub fn iterations(mut self, iterations: c_ulong) -> Self {
+        self.iterations = Some(iterations);
+        self
+    }
+
+    /// Set the optimisation applied before writing, [Optimization::None] by
+    /// default.
+    pub fn optimization(mut self, optimization: Optimization) -> Self {
+        self.optimization = optimization;
+        self
+    }
+
+    /// Set the format written by [AnimationBuilder::build], `"GIF"` by
+    /// default.
+    pub fn format(mut self, format: impl Into<String>) -> Self {
+        self.format = format.into();
+        self
+    }
+
+    /// Number of frames added so far.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Whether no frame has been added.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Check the frames, and reset their iterator to their single image.
+    ///
+    /// # Return
+    ///
+    /// The size of the canvas.
+    fn validate(&mut self) -> Result<(c_ulong, c_ulong), ValidationError> {
+        if self.frames.is_empty() {
+            return Err(ValidationError::NoFrames);
+        }
+        let mut expected = None;
+        for (index, frame) in self.frames.iter_mut().enumerate() {
+            let images = frame.wand.get_number_images();
+            if images != 1 {
+                return Err(ValidationError::NotSingleImage { index, images });
+            }
+            frame.wand.reset_iterator();
+            let width = frame.wand.get_image_width();
+            let height = frame.wand.get_image_height();
+
+            if let Some((canvas_width, canvas_height)) = self.canvas {
+                if width > canvas_width || height > canvas_height {
+                    return Err(ValidationError::LargerThanCanvas {
+                        index,
+                        width,
+                        height,
+                        canvas_width,
+                        canvas_height,
+                    });
+                }
+            } else if let Some((expected_width, expected_height)) = expected {
+                if width != expected_width || height != expected_height {
+                    return Err(ValidationError::SizeMismatch {
+                        index,
+                        width,
+                        height,
+                        expected_width,
+                        expected_height,
+                    });
+                }
+            } else {
+                expected = Some((width, height));
+            }
+        }
+        Ok(self
+            .canvas
+            .or(expected)
+            .expect("there is at least one frame"))
+    }
+
+    /// Validate the frames, then glue them into a sequence and write it as
+    /// an animated blob in the format of [AnimationBuilder::format].
+    pub fn build(mut self) -> crate::Result<Vec<u8>> {
+        let (width, height) = self.validate()?;
+
+        let mut sequence = MagickWand::new();
+        for frame in &self.frames {
+            sequence.add_image(&frame.wand)?;
+        }
+        self.apply_frame_settings(&mut sequence, Some((width, height)))?;
+
+        match self.optimize(&mut sequence) {
+            None => self.write_blob(&mut sequence),
+            Some(optimized) => {
+                let mut optimized = optimized?;
+                // The page of every frame is set by the optimisation.
+                self.apply_frame_settings(&mut optimized, None)?;
+                self.write_blob(&mut optimized)
+            }
+        }
+    }
+
+    /// Apply [AnimationBuilder::optimization] to `sequence`.
+    ///
+    /// # Return
+    ///
+    /// `None` for [Optimization::None], else the optimised sequence, which
+    /// doesn't borrow `sequence`, unlike [MagickWand::coalesce_images].
+    fn optimize(
+        &self,
+        sequence: &mut MagickWand<'_>,
+    ) -> Option<crate::Result<MagickWand<'static>>> {
+        let optimize = match self.optimization {
+            Optimization::None => return None,
+            Optimization::Coalesce => MagickCoalesceImages,
+            Optimization::Deconstruct => MagickDeconstructImages,
+        };
+        sequence.reset_iterator();
+        let optimized = unsafe { MagickWand::from_wand(optimize(sequence.wand_mut())) };
+        Some(optimized.ok_or_else(|| unsafe { sequence.get_error() }))
+    }
+
+    /// Set the delay, disposal, loop count and, if given, page of every image
+    /// of `sequence`, which holds one image per frame.
+    fn apply_frame_settings(
+        &self,
+        sequence: &mut MagickWand<'_>,
+        page: Option<(c_ulong, c_ulong)>,
+    ) -> crate::Result<()> {
+        let mut images = sequence.frames_mut();
+        for frame in &self.frames {
+            let Some(mut image) = images.next_frame() else {
+                break;
+            };
+            image.set_delay(frame.delay)?.set_dispose(frame.dispose)?;
+            if let Some((width, height)) = page {
+                image.set_page(width, height, 0, 0)?;
+            }
+            #[cfg(feature = "v1_3_26")]
+            if let Some(iterations) = self.iterations {
+                image.set_iterations(iterations)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the whole `sequence`, which the blob does from the current
+    /// image on.
+    fn write_blob(&self, sequence: &mut MagickWand<'_>) -> crate::Result<Vec<u8>> {
+        sequence.reset_iterator();
+        sequence.set_image_format(self.format.as_str())?;
+        match sequence.write_image_blob() {
+            Some(blob) => Ok(blob.to_vec()),
+            None => Err(unsafe { sequence.get_error() }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::new_color_magick_wand;
+
+    fn read_blob(blob: &[u8]) -> MagickWand<'_> {
+        let mut mw = MagickWand::new();
+        mw.read_image_blob(blob).unwrap();
+        mw
+    }
+
+    #[test]
+    fn test_animation_builder_validate() {
+        let err = AnimationBuilder::new().validate().unwrap_err();
+        assert_eq!(err, ValidationError::NoFrames);
+
+        let mut builder = AnimationBuilder::new()
+            .frame(
+                new_color_magick_wand("red", 4, 4),
+                10,
+                DisposeType::NoneDispose,
+            )
+            .frame(
+                new_color_magick_wand("blue", 4, 4),
+                10,
+                DisposeType::NoneDispose,
+            );
+        assert_eq!(builder.validate().unwrap(), (4, 4));
+
+        let mut builder = builder.frame(
+            new_color_magick_wand("green", 4, 2),
+            10,
+            DisposeType::NoneDispose,
+        );
+        assert!(matches!(
+            builder.validate().unwrap_err(),
+            ValidationError::SizeMismatch { index: 2, .. }
+        ));
+
+        let mut builder = builder.canvas(4, 4);
+        assert_eq!(builder.validate().unwrap(), (4, 4));
+
+        let mut builder = builder.canvas(4, 2).frame(
+            new_color_magick_wand("white", 4, 2),
+            10,
+            DisposeType::NoneDispose,
+        );
+        assert!(matches!(
+            builder.validate().unwrap_err(),
+            ValidationError::LargerThanCanvas { index: 0, .. }
+        ));
+
+        let mut two = new_color_magick_wand("red", 4, 4);
+        two.read_image("xc:blue").unwrap();
+        let err = AnimationBuilder::new()
+            .frame(two, 10, DisposeType::NoneDispose)
+            .validate()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ValidationError::NotSingleImage {
+                index: 0,
+                images: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_animation_builder_build() {
+        let blob = AnimationBuilder::new()
+            .frame(
+                new_color_magick_wand("red", 8, 8),
+                10,
+                DisposeType::NoneDispose,
+            )
+            .frame(
+                new_color_magick_wand("blue", 8, 8),
+                20,
+                DisposeType::BackgroundDispose,
+            )
+            .frame(
+                new_color_magick_wand("green", 8, 8),
+                30,
+                DisposeType::NoneDispose,
+            )
+            .build()
+            .unwrap();
+
+        let mut mw = read_blob(&blob);
+        assert_eq!(mw.get_image_format().to_str().unwrap(), "GIF");
+        assert_eq!(mw.get_number_images(), 3);
+
+        let mut delays = Vec::new();
+        let mut frames = mw.frames();
+        while let Some(mut frame) = frames.next_frame() {
+            delays.push(frame.delay());
+        }
+        assert_eq!(delays, [10, 20, 30]);
+    }
+
+    #[test]
+    fn test_animation_builder_build_canvas() {
+        let blob = AnimationBuilder::new()
+            .canvas(16, 16)
+            .frame(
+                new_color_magick_wand("red", 8, 8),
+                10,
+                DisposeType::NoneDispose,
+            )
+            .frame(
+                new_color_magick_wand("blue", 16, 4),
+                10,
+                DisposeType::NoneDispose,
+            )
+            .build()
+            .unwrap();
+
+        let mut mw = read_blob(&blob);
+        assert_eq!(mw.get_number_images(), 2);
+        let (width, height, ..) = mw.get_image_page().unwrap();
+        assert_eq!((width, height), (16, 16));
+    }
+
+    #[test]
+    fn test_animation_builder_build_optimization() {
+        for optimization in [Optimization::Coalesce, Optimization::Deconstruct] {
+            let blob = AnimationBuilder::new()
+                .optimization(optimization)
+                .frame(
+                    new_color_magick_wand("red", 8, 8),
+                    10,
+                    DisposeType::NoneDispose,
+                )
+                .frame(
+                    new_color_magick_wand("blue", 8, 8),
+                    20,
+                    DisposeType::NoneDispose,
+                )
+                .build()
+                .unwrap();
+
+            let mut mw = read_blob(&blob);
+            assert_eq!(mw.get_number_images(), 2);
+            mw.set_image_index(1).unwrap();
+            assert_eq!(mw.get_image_delay(), 20);
+        }
+    }
+
+    #[cfg(feature = "v1_3_26")]
+    #[test]
+    fn test_animation_builder_build_iterations() {
+        let blob = AnimationBuilder::new()
+            .iterations(3)
+            .frame(
+                new_color_magick_wand("red", 8, 8),
+                10,
+                DisposeType::NoneDispose,
+            )
+            .frame(
+                new_color_magick_wand("blue", 8, 8),
+                10,
+                DisposeType::NoneDispose,
+            )
+            .build()
+            .unwrap();
+
+        let mut mw = read_blob(&blob);
+        assert_eq!(mw.get_image_iterations(), 3);
+    }
+}
@@ -0,0 +1,378 @@
+//! Structured comparison of two images.
+//!
+//! [MagickWand::compare_report] computes several [MetricType]s at once, per
+//! channel, along with an image highlighting the changed pixels, see
+//! [CompareReport].
+//!
+//! ```no_run
+//! use graphicsmagick::{initialize, types::MetricType, wand::MagickWand};
+//!
+//! initialize();
+//!
+//! let mut actual = MagickWand::new();
+//! actual.read_image("actual.png").unwrap();
+//! let mut expected = MagickWand::new();
+//! expected.read_image("expected.png").unwrap();
+//!
+//! let mut report = actual
+//!     .compare_report(
+//!         &mut expected,
+//!         &[
+//!             MetricType::RootMeanSquaredErrorMetric,
+//!             MetricType::PeakSignalToNoiseRatioMetric,
+//!         ],
+//!     )
+//!     .unwrap();
+//! if !report.is_within(MetricType::RootMeanSquaredErrorMetric, 0.01) {
+//!     eprintln!("{}", report);
+//!     report.difference_mut().write_image("diff.png").unwrap();
+//! }
+//! ```
+
+use crate::{
+    error::{Exception, ExceptionType, with_exception_info},
+    types::{ChannelType, MetricType},
+    wand::{MagickWand, magick::MagickWandImportSlice},
+};
+use graphicsmagick_sys::{
+    DifferenceStatistics, GetImageChannelDifference, GetImageFromMagickWand,
+    InitializeDifferenceStatistics, MagickFail,
+};
+use std::{fmt, mem::MaybeUninit, os::raw::c_double};
+
+/// Channels compared by [MagickWand::compare_report], besides
+/// [ChannelType::AllChannels].
+pub const CHANNELS: [ChannelType; 4] = [
+    ChannelType::RedChannel,
+    ChannelType::GreenChannel,
+    ChannelType::BlueChannel,
+    ChannelType::OpacityChannel,
+];
+
+/// Color of the changed pixels in [CompareReport::difference].
+const HIGHLIGHT: [u8; 4] = [255, 0, 0, 255];
+
+/// Distortions of one [MetricType].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricDistortion {
+    metric: MetricType,
+    overall: c_double,
+    channels: Vec<(ChannelType, c_double)>,
+}
+
+impl MetricDistortion {
+    pub fn metric(&self) -> MetricType {
+        self.metric
+    }
+
+    /// Distortion of [ChannelType::AllChannels].
+    pub fn overall(&self) -> c_double {
+        self.overall
+    }
+
+    /// Distortion of each of [CHANNELS].
+    pub fn channels(&self) -> &[(ChannelType, c_double)] {
+        &self.channels
+    }
+
+    /// Distortion of `channel`, `None` if it wasn't compared.
+    pub fn channel(&self, channel: ChannelType) -> Option<c_double> {
+        if channel == ChannelType::AllChannels {
+            return Some(self.overall);
+        }
+        self.channels
+            .iter()
+            .find(|(c, _)| *c == channel)
+            .map(|(_, distortion)| *distortion)
+    }
+
+    /// Whether the overall distortion is within `tolerance`: at most
+    /// `tolerance`, or for [MetricType::PeakSignalToNoiseRatioMetric], where
+    /// higher means closer, at least `tolerance` decibels.
+    pub fn is_within(&self, tolerance: c_double) -> bool {
+        if self.metric == MetricType::PeakSignalToNoiseRatioMetric {
+            self.overall >= tolerance
+        } else {
+            self.overall <= tolerance
+        }
+    }
+}
+
+/// Result of [MagickWand::compare_report].
+#[derive(Debug)]
+pub struct CompareReport {
+    distortions: Vec<MetricDistortion>,
+    difference: MagickWand<'static>,
+    changed_pixels: usize,
+}
+
+impl CompareReport {
+    /// Distortions, in the order of the requested metrics.
+    pub fn distortions(&self) -> &[MetricDistortion] {
+        &self.distortions
+    }
+
+    /// Distortions of `metric`, `None` if it wasn't requested.
+    pub fn distortion(&self, metric: MetricType) -> Option<&MetricDistortion> {
+        self.distortions.iter().find(|d| d.metric == metric)
+    }
+
+    /// See [MetricDistortion::is_within].
+    ///
+    /// # Panic
+    ///
+    /// Panic if `metric` wasn't requested.
+    pub fn is_within(&self, metric: MetricType, tolerance: c_double) -> bool {
+        self.distortion(metric)
+            .unwrap_or_else(|| panic!("{:?} wasn't compared", metric))
+            .is_within(tolerance)
+    }
+
+    /// Number of pixels which differ in any channel.
+    pub fn changed_pixels(&self) -> usize {
+        self.changed_pixels
+    }
+
+    /// Image of the reference, faded, with the changed pixels in red.
+    pub fn difference(&self) -> &MagickWand<'static> {
+        &self.difference
+    }
+
+    /// Like [CompareReport::difference], mutable, e.g. to write it.
+    pub fn difference_mut(&mut self) -> &mut MagickWand<'static> {
+        &mut self.difference
+    }
+
+    /// Take the difference image.
+    pub fn into_difference(self) -> MagickWand<'static> {
+        self.difference
+    }
+}
+
+impl fmt::Display for CompareReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} changed pixels", self.changed_pixels)?;
+        for distortion in &self.distortions {
+            write!(f, "; {:?}: {}", distortion.metric, distortion.overall)?;
+            for (i, (channel, value)) in distortion.channels.iter().enumerate() {
+                let separator = if i == 0 { " (" } else { ", " };
+                write!(f, "{}{:?}: {}", separator, channel, value)?;
+            }
+            if !distortion.channels.is_empty() {
+                f.write_str(")")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl MagickWand<'_> {
+    /// Compare the current image with the current image of `reference`, with
+    /// each of `metrics`, overall and for each of [CHANNELS].
+    ///
+    /// Unlike [MagickWand::compare_images], errors, such as images of
+    /// different sizes, are reported, and the report includes an image of the
+    /// changed pixels.
+    pub fn compare_report(
+        &mut self,
+        reference: &mut MagickWand<'_>,
+        metrics: &[MetricType],
+    ) -> crate::Result<CompareReport> {
+        // Checked first, as the size getters throw into the wand exception
+        // without an image.
+        self.check_has_images()?;
+        reference.check_has_images()?;
+        let (columns, rows) = (self.get_image_width(), self.get_image_height());
+        if (columns, rows) != (reference.get_image_width(), reference.get_image_height()) {
+            return Err(Exception::new(
+                ExceptionType::ImageError,
+                "image size differs".to_owned(),
+                None,
+            )
+            .into());
+        }
+
+        let distortions = metrics
+            .iter()
+            .map(|&metric| self.metric_distortion(reference, metric))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        let actual = self.get_image_pixels::<u8>(0, 0, columns, rows, "RGBA")?;
+        let expected = reference.get_image_pixels::<u8>(0, 0, columns, rows, "RGBA")?;
+        let mut changed_pixels = 0;
+        let pixels: Vec<u8> = actual
+            .chunks_exact(4)
+            .zip(expected.chunks_exact(4))
+            .flat_map(|(actual, expected)| {
+                if actual != expected {
+                    changed_pixels += 1;
+                    HIGHLIGHT
+                } else {
+                    // Fade towards white, to keep the context visible.
+                    let fade = |value: u8| 255 - (255 - value) / 4;
+                    [fade(expected[0]), fade(expected[1]), fade(expected[2]), 255]
+                }
+            })
+            .collect();
+
+        let mut difference = MagickWand::new();
+        difference.set_size(columns, rows)?;
+        difference.read_image("xc:white")?;
+        let input = MagickWandImportSlice::new(columns, rows, "RGBA", &pixels)
+            .expect("difference pixels are complete");
+        difference.set_image_pixels(0, 0, input)?;
+
+        Ok(CompareReport {
+            distortions,
+            difference,
+            changed_pixels,
+        })
+    }
+
+    /// Distortions of `metric`, overall and for each of [CHANNELS], computed
+    /// in a single pass by `GetImageChannelDifference`, without the
+    /// difference image [MagickWand::compare_image_channels] allocates.
+    fn metric_distortion(
+        &mut self,
+        reference: &mut MagickWand<'_>,
+        metric: MetricType,
+    ) -> crate::Result<MetricDistortion> {
+        // `GetImageFromMagickWand` would throw into the wand exception, and
+        // leave it to the next failed call.
+        self.check_has_images()?;
+        reference.check_has_images()?;
+        let image = unsafe { GetImageFromMagickWand(self.wand()) };
+        let reference_image = unsafe { GetImageFromMagickWand(reference.wand()) };
+        let statistics = with_exception_info(|exception| unsafe {
+            let mut statistics = MaybeUninit::<DifferenceStatistics>::uninit();
+            InitializeDifferenceStatistics(statistics.as_mut_ptr(), exception);
+            let status = GetImageChannelDifference(
+                image,
+                reference_image,
+                metric.into(),
+                statistics.as_mut_ptr(),
+                exception,
+            );
+            if status == MagickFail {
+                return Err(crate::Error::from(Exception::from_exception_info(
+                    exception,
+                )));
+            }
+            Ok(statistics.assume_init())
+        })?;
+        Ok(MetricDistortion {
+            metric,
+            overall: statistics.combined,
+            channels: vec![
+                (ChannelType::RedChannel, statistics.red),
+                (ChannelType::GreenChannel, statistics.green),
+                (ChannelType::BlueChannel, statistics.blue),
+                (ChannelType::OpacityChannel, statistics.opacity),
+            ],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::new_logo_magick_wand, types::FilterTypes};
+
+    const METRICS: [MetricType; 3] = [
+        MetricType::MeanAbsoluteErrorMetric,
+        MetricType::RootMeanSquaredErrorMetric,
+        MetricType::PeakSignalToNoiseRatioMetric,
+    ];
+
+    #[test]
+    fn test_compare_report_identical() {
+        let mut mw = new_logo_magick_wand();
+        let mut reference = mw.clone();
+        let report = mw.compare_report(&mut reference, &METRICS).unwrap();
+
+        assert_eq!(report.changed_pixels(), 0);
+        assert_eq!(report.distortions().len(), 3);
+        let rmse = report
+            .distortion(MetricType::RootMeanSquaredErrorMetric)
+            .unwrap();
+        assert_eq!(rmse.overall(), 0.);
+        assert_eq!(rmse.channels().len(), CHANNELS.len());
+        assert!(report.is_within(MetricType::RootMeanSquaredErrorMetric, 0.));
+        assert!(report.is_within(MetricType::PeakSignalToNoiseRatioMetric, 60.));
+        assert_eq!(report.difference().clone().get_image_width(), 1024);
+    }
+
+    #[test]
+    fn test_compare_report_changed() {
+        let mut mw = new_logo_magick_wand();
+        let mut reference = mw.clone();
+        mw.blur_image(0., 4.).unwrap();
+        let mut report = mw.compare_report(&mut reference, &METRICS).unwrap();
+
+        assert!(report.changed_pixels() > 0);
+        let mae = report
+            .distortion(MetricType::MeanAbsoluteErrorMetric)
+            .unwrap();
+        assert!(mae.overall() > 0.);
+        assert!(mae.channel(ChannelType::RedChannel).unwrap() > 0.);
+        assert_eq!(mae.channel(ChannelType::AllChannels), Some(mae.overall()));
+        assert!(!report.is_within(MetricType::MeanAbsoluteErrorMetric, 0.));
+        assert!(report.is_within(MetricType::MeanAbsoluteErrorMetric, 1.));
+        assert!(report.to_string().contains("MeanAbsoluteErrorMetric"));
+
+        let difference = report.difference_mut();
+        assert_eq!(difference.get_image_width(), mw.get_image_width());
+        assert_eq!(difference.get_image_height(), mw.get_image_height());
+    }
+
+    #[test]
+    fn test_compare_report_metric_distortion() {
+        let mut mw = new_logo_magick_wand();
+        let mut reference = mw.clone();
+        mw.blur_image(0., 4.).unwrap();
+        for metric in METRICS {
+            let distortion = mw.metric_distortion(&mut reference, metric).unwrap();
+            let channels = [(ChannelType::AllChannels, distortion.overall())]
+                .into_iter()
+                .chain(distortion.channels().iter().copied());
+            // Same as the wand API, which allocates a difference image.
+            for (channel, value) in channels {
+                let mut expected = 0.;
+                mw.compare_image_channels(&reference, channel, metric, &mut expected)
+                    .unwrap();
+                assert_eq!(value, expected, "{:?} {:?}", metric, channel);
+            }
+        }
+    }
+
+    #[test]
+    fn test_compare_report_size_differs() {
+        let mut mw = new_logo_magick_wand();
+        let mut reference = mw.clone();
+        reference
+            .resize_image(100, 100, FilterTypes::LanczosFilter, 1.)
+            .unwrap();
+        let e = mw.compare_report(&mut reference, &METRICS).unwrap_err();
+        assert_eq!(
+            e.exception().map(Exception::get_exception_type),
+            Some(ExceptionType::ImageError)
+        );
+    }
+
+    #[test]
+    fn test_compare_report_no_images() {
+        let mut mw = new_logo_magick_wand();
+        let mut empty = MagickWand::new();
+        let e = mw.compare_report(&mut empty, &METRICS).unwrap_err();
+        assert_eq!(
+            e.exception().map(Exception::get_exception_type),
+            Some(ExceptionType::WandError)
+        );
+        // No stale `WandContainsNoImages` left for the next failed call.
+        let e = empty.read_image("/not/exists/logo.png").unwrap_err();
+        assert_ne!(
+            e.exception().map(Exception::get_exception_type),
+            Some(ExceptionType::WandError)
+        );
+    }
+}
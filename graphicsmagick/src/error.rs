@@ -1,6 +1,8 @@
 //! Crate level errors.
 
+use graphicsmagick_sys::{DestroyExceptionInfo, ExceptionInfo, GetExceptionInfo};
 use num_enum::{FromPrimitive, IntoPrimitive};
+use std::{ffi::CStr, fmt, io, mem::MaybeUninit, os::raw::c_char};
 use thiserror::Error as ThisError;
 
 /// Crate result.
@@ -13,28 +15,262 @@ pub enum Error {
     /// GraphicsMagick Exception.
     #[error(transparent)]
     Exception(#[from] Exception),
+
+    /// Invalid [Pipeline](crate::pipeline::Pipeline).
+    #[error(transparent)]
+    Pipeline(#[from] crate::pipeline::ValidationError),
+
+    /// Invalid [AnimationBuilder](crate::animation::AnimationBuilder).
+    #[error(transparent)]
+    Animation(#[from] crate::animation::ValidationError),
+
+    /// Invalid EXIF profile, see [Exif](crate::exif::Exif).
+    #[error(transparent)]
+    Exif(#[from] crate::exif::ParseExifError),
+
+    /// Invalid IPTC profile, see [Iptc](crate::iptc::Iptc).
+    #[error(transparent)]
+    Iptc(#[from] crate::iptc::ParseIptcError),
+
+    /// Invalid XMP profile, see [Xmp](crate::xmp::Xmp).
+    #[error(transparent)]
+    Xmp(#[from] crate::xmp::ParseXmpError),
+
+    /// IO error of a reader or writer.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// Conversion between a [MagickWand](crate::wand::MagickWand) and the
+    /// type of another crate failed.
+    #[error("conversion failed: {0}")]
+    Conversion(String),
+
+    /// An asynchronous operation was cancelled before it ran, e.g. because
+    /// the tokio runtime was shutting down.
+    #[error("operation cancelled")]
+    Cancelled,
+}
+
+impl Error {
+    /// The GraphicsMagick exception, if the error comes from GraphicsMagick.
+    pub fn exception(&self) -> Option<&Exception> {
+        match self {
+            Error::Exception(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// See [ExceptionType::severity], `None` if the error doesn't come from
+    /// GraphicsMagick.
+    pub fn severity(&self) -> Option<ExceptionSeverity> {
+        self.exception().map(Exception::severity)
+    }
+
+    /// See [ExceptionType::category], `None` if the error doesn't come from
+    /// GraphicsMagick.
+    pub fn category(&self) -> Option<ExceptionCategory> {
+        self.exception().map(Exception::category)
+    }
+
+    /// Whether the same operation may succeed if tried again later, see
+    /// [ExceptionType::is_retryable].
+    ///
+    /// IO errors are retryable if they are interrupted, would block or timed
+    /// out.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Exception(e) => e.is_retryable(),
+            Error::Io(e) => matches!(
+                e.kind(),
+                io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+            ),
+            Error::Pipeline(_)
+            | Error::Animation(_)
+            | Error::Exif(_)
+            | Error::Iptc(_)
+            | Error::Xmp(_)
+            | Error::Conversion(_)
+            | Error::Cancelled => false,
+        }
+    }
 }
 
 /// Wrapper of `graphicsmagick_sys::ExceptionType` and ExceptionInfo.
-#[derive(ThisError, Debug, Eq, PartialEq)]
-#[error("kind: {kind:?}, description: {description}")]
+///
+/// GraphicsMagick reports an exception as a reason, e.g. "Unable to open
+/// file", and an optional description, e.g. the file name.
+#[derive(ThisError, Debug, Clone, Eq, PartialEq)]
+#[error("kind: {kind:?}, reason: {reason}{}", DisplayDescription(.description))]
 pub struct Exception {
     kind: ExceptionType,
-    description: String,
+    reason: String,
+    description: Option<String>,
 }
 
 impl Exception {
-    pub fn new(kind: ExceptionType, description: String) -> Self {
-        Exception { kind, description }
+    pub fn new(kind: ExceptionType, reason: String, description: Option<String>) -> Self {
+        Exception {
+            kind,
+            reason,
+            description,
+        }
+    }
+
+    /// Split a message formatted by `MagickGetException`, which is
+    /// `"reason (description)"`, or just `"reason"`.
+    pub(crate) fn from_message(kind: ExceptionType, message: &str) -> Self {
+        let (reason, description) = match message
+            .strip_suffix(')')
+            .and_then(|message| message.split_once(" ("))
+        {
+            Some((reason, description)) => (reason, Some(description.to_owned())),
+            None => (message, None),
+        };
+        Exception::new(kind, reason.to_owned(), description)
+    }
+
+    /// Copy the exception of an `ExceptionInfo` of the core API.
+    ///
+    /// # Safety
+    ///
+    /// The reason and description of `exception` must be null or valid
+    /// strings.
+    pub(crate) unsafe fn from_exception_info(exception: &ExceptionInfo) -> Self {
+        let to_string = |s: *const c_char| {
+            (!s.is_null()).then(|| unsafe { CStr::from_ptr(s) }.to_string_lossy().into_owned())
+        };
+        let reason = to_string(exception.reason).unwrap_or_else(|| "Unknown exception".to_owned());
+        Exception::new(
+            exception.severity.into(),
+            reason,
+            to_string(exception.description),
+        )
     }
 
     pub fn get_exception_type(&self) -> ExceptionType {
         self.kind
     }
 
-    pub fn get_description(&self) -> &str {
-        &self.description
+    /// Reason of the exception.
+    pub fn get_reason(&self) -> &str {
+        &self.reason
+    }
+
+    /// Description of the exception, which usually names the object (file,
+    /// format, option, ...) the reason is about.
+    pub fn get_description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// See [ExceptionType::severity].
+    pub fn severity(&self) -> ExceptionSeverity {
+        self.kind.severity()
+    }
+
+    /// See [ExceptionType::category].
+    pub fn category(&self) -> ExceptionCategory {
+        self.kind.category()
     }
+
+    /// See [ExceptionType::is_retryable].
+    pub fn is_retryable(&self) -> bool {
+        self.kind.is_retryable()
+    }
+}
+
+struct DisplayDescription<'a>(&'a Option<String>);
+
+impl fmt::Display for DisplayDescription<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(description) => write!(f, ", description: {}", description),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Call `f` with a new `ExceptionInfo` of the core API, destroyed afterwards.
+pub(crate) fn with_exception_info<T>(f: impl FnOnce(&mut ExceptionInfo) -> T) -> T {
+    unsafe {
+        let mut exception = MaybeUninit::<ExceptionInfo>::uninit();
+        GetExceptionInfo(exception.as_mut_ptr());
+        let mut exception = exception.assume_init();
+        let result = f(&mut exception);
+        DestroyExceptionInfo(&mut exception);
+        result
+    }
+}
+
+/// Severity of an [ExceptionType].
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash)]
+pub enum ExceptionSeverity {
+    /// `UndefinedException` or unknown code.
+    Undefined,
+    /// Event, only used for logging.
+    Event,
+    /// Warning, the operation succeeded but the result may be incomplete.
+    Warning,
+    /// Error, the operation failed.
+    Error,
+    /// Fatal error, GraphicsMagick can't continue.
+    FatalError,
+}
+
+/// Category of an [ExceptionType], i.e. the subsystem which raised it.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+pub enum ExceptionCategory {
+    /// `UndefinedException` or unknown code.
+    Undefined,
+    /// Generic exception, e.g. `ErrorException`.
+    Generic,
+    /// Resource limit exceeded or resource exhausted.
+    Resource,
+    /// Font or type (annotation) problem.
+    Type,
+    /// Invalid option or argument.
+    Option,
+    /// External delegate program failed.
+    Delegate,
+    /// No delegate or coder for the format.
+    MissingDelegate,
+    /// Corrupt or truncated image data.
+    CorruptImage,
+    /// File can't be opened.
+    FileOpen,
+    /// Blob (in-memory or file IO) problem.
+    Blob,
+    /// Stream problem.
+    Stream,
+    /// Pixel cache problem.
+    Cache,
+    /// Coder problem.
+    Coder,
+    /// Module loading problem.
+    Module,
+    /// Drawing or rendering problem.
+    Draw,
+    /// Image problem.
+    Image,
+    /// Wand problem, e.g. the wand has no images.
+    Wand,
+    /// Temporary file problem.
+    TemporaryFile,
+    /// Transform problem.
+    Transform,
+    /// X server (X11) problem.
+    XServer,
+    /// User defined.
+    User,
+    /// Progress monitor, e.g. the operation was cancelled.
+    Monitor,
+    /// Locale problem.
+    Locale,
+    /// Deprecated feature used.
+    Deprecate,
+    /// Registry problem.
+    Registry,
+    /// Configuration problem.
+    Configure,
 }
 
 /// Wrapper of [ExceptionType](http://www.graphicsmagick.org/api/types.html#exceptiontype).
@@ -166,3 +402,334 @@ pub enum ExceptionType {
     #[num_enum(default)]
     Unknown = u32::MAX,
 }
+
+impl ExceptionType {
+    /// Severity of the exception type.
+    pub fn severity(self) -> ExceptionSeverity {
+        match self {
+            ExceptionType::UndefinedException | ExceptionType::Unknown => {
+                ExceptionSeverity::Undefined
+            }
+            ExceptionType::EventException
+            | ExceptionType::ExceptionEvent
+            | ExceptionType::ResourceEvent
+            | ExceptionType::TypeEvent
+            | ExceptionType::OptionEvent
+            | ExceptionType::DelegateEvent
+            | ExceptionType::MissingDelegateEvent
+            | ExceptionType::CorruptImageEvent
+            | ExceptionType::FileOpenEvent
+            | ExceptionType::BlobEvent
+            | ExceptionType::StreamEvent
+            | ExceptionType::CacheEvent
+            | ExceptionType::CoderEvent
+            | ExceptionType::ModuleEvent
+            | ExceptionType::DrawEvent
+            | ExceptionType::ImageEvent
+            | ExceptionType::WandEvent
+            | ExceptionType::TemporaryFileEvent
+            | ExceptionType::TransformEvent
+            | ExceptionType::XServerEvent
+            | ExceptionType::X11Event
+            | ExceptionType::UserEvent
+            | ExceptionType::MonitorEvent
+            | ExceptionType::LocaleEvent
+            | ExceptionType::DeprecateEvent
+            | ExceptionType::RegistryEvent
+            | ExceptionType::ConfigureEvent => ExceptionSeverity::Event,
+            ExceptionType::WarningException
+            | ExceptionType::ExceptionWarning
+            | ExceptionType::ResourceWarning
+            | ExceptionType::TypeWarning
+            | ExceptionType::OptionWarning
+            | ExceptionType::DelegateWarning
+            | ExceptionType::MissingDelegateWarning
+            | ExceptionType::CorruptImageWarning
+            | ExceptionType::FileOpenWarning
+            | ExceptionType::BlobWarning
+            | ExceptionType::StreamWarning
+            | ExceptionType::CacheWarning
+            | ExceptionType::CoderWarning
+            | ExceptionType::ModuleWarning
+            | ExceptionType::DrawWarning
+            | ExceptionType::ImageWarning
+            | ExceptionType::WandWarning
+            | ExceptionType::TemporaryFileWarning
+            | ExceptionType::TransformWarning
+            | ExceptionType::XServerWarning
+            | ExceptionType::X11Warning
+            | ExceptionType::UserWarning
+            | ExceptionType::MonitorWarning
+            | ExceptionType::LocaleWarning
+            | ExceptionType::DeprecateWarning
+            | ExceptionType::RegistryWarning
+            | ExceptionType::ConfigureWarning => ExceptionSeverity::Warning,
+            ExceptionType::ErrorException
+            | ExceptionType::ExceptionError
+            | ExceptionType::ResourceError
+            | ExceptionType::TypeError
+            | ExceptionType::OptionError
+            | ExceptionType::DelegateError
+            | ExceptionType::MissingDelegateError
+            | ExceptionType::CorruptImageError
+            | ExceptionType::FileOpenError
+            | ExceptionType::BlobError
+            | ExceptionType::StreamError
+            | ExceptionType::CacheError
+            | ExceptionType::CoderError
+            | ExceptionType::ModuleError
+            | ExceptionType::DrawError
+            | ExceptionType::ImageError
+            | ExceptionType::WandError
+            | ExceptionType::TemporaryFileError
+            | ExceptionType::TransformError
+            | ExceptionType::XServerError
+            | ExceptionType::X11Error
+            | ExceptionType::UserError
+            | ExceptionType::MonitorError
+            | ExceptionType::LocaleError
+            | ExceptionType::DeprecateError
+            | ExceptionType::RegistryError
+            | ExceptionType::ConfigureError => ExceptionSeverity::Error,
+            ExceptionType::FatalErrorException
+            | ExceptionType::ExceptionFatalError
+            | ExceptionType::ResourceFatalError
+            | ExceptionType::TypeFatalError
+            | ExceptionType::OptionFatalError
+            | ExceptionType::DelegateFatalError
+            | ExceptionType::MissingDelegateFatalError
+            | ExceptionType::CorruptImageFatalError
+            | ExceptionType::FileOpenFatalError
+            | ExceptionType::BlobFatalError
+            | ExceptionType::StreamFatalError
+            | ExceptionType::CacheFatalError
+            | ExceptionType::CoderFatalError
+            | ExceptionType::ModuleFatalError
+            | ExceptionType::DrawFatalError
+            | ExceptionType::ImageFatalError
+            | ExceptionType::WandFatalError
+            | ExceptionType::TemporaryFileFatalError
+            | ExceptionType::TransformFatalError
+            | ExceptionType::XServerFatalError
+            | ExceptionType::X11FatalError
+            | ExceptionType::UserFatalError
+            | ExceptionType::MonitorFatalError
+            | ExceptionType::LocaleFatalError
+            | ExceptionType::DeprecateFatalError
+            | ExceptionType::RegistryFatalError
+            | ExceptionType::ConfigureFatalError => ExceptionSeverity::FatalError,
+        }
+    }
+
+    /// Category of the exception type.
+    pub fn category(self) -> ExceptionCategory {
+        match self {
+            ExceptionType::UndefinedException | ExceptionType::Unknown => {
+                ExceptionCategory::Undefined
+            }
+            ExceptionType::EventException
+            | ExceptionType::ExceptionEvent
+            | ExceptionType::WarningException
+            | ExceptionType::ExceptionWarning
+            | ExceptionType::ErrorException
+            | ExceptionType::ExceptionError
+            | ExceptionType::FatalErrorException
+            | ExceptionType::ExceptionFatalError => ExceptionCategory::Generic,
+            ExceptionType::ResourceEvent
+            | ExceptionType::ResourceWarning
+            | ExceptionType::ResourceError
+            | ExceptionType::ResourceFatalError => ExceptionCategory::Resource,
+            ExceptionType::TypeEvent
+            | ExceptionType::TypeWarning
+            | ExceptionType::TypeError
+            | ExceptionType::TypeFatalError => ExceptionCategory::Type,
+            ExceptionType::OptionEvent
+            | ExceptionType::OptionWarning
+            | ExceptionType::OptionError
+            | ExceptionType::OptionFatalError => ExceptionCategory::Option,
+            ExceptionType::DelegateEvent
+            | ExceptionType::DelegateWarning
+            | ExceptionType::DelegateError
+            | ExceptionType::DelegateFatalError => ExceptionCategory::Delegate,
+            ExceptionType::MissingDelegateEvent
+            | ExceptionType::MissingDelegateWarning
+            | ExceptionType::MissingDelegateError
+            | ExceptionType::MissingDelegateFatalError => ExceptionCategory::MissingDelegate,
+            ExceptionType::CorruptImageEvent
+            | ExceptionType::CorruptImageWarning
+            | ExceptionType::CorruptImageError
+            | ExceptionType::CorruptImageFatalError => ExceptionCategory::CorruptImage,
+            ExceptionType::FileOpenEvent
+            | ExceptionType::FileOpenWarning
+            | ExceptionType::FileOpenError
+            | ExceptionType::FileOpenFatalError => ExceptionCategory::FileOpen,
+            ExceptionType::BlobEvent
+            | ExceptionType::BlobWarning
+            | ExceptionType::BlobError
+            | ExceptionType::BlobFatalError => ExceptionCategory::Blob,
+            ExceptionType::StreamEvent
+            | ExceptionType::StreamWarning
+            | ExceptionType::StreamError
+            | ExceptionType::StreamFatalError => ExceptionCategory::Stream,
+            ExceptionType::CacheEvent
+            | ExceptionType::CacheWarning
+            | ExceptionType::CacheError
+            | ExceptionType::CacheFatalError => ExceptionCategory::Cache,
+            ExceptionType::CoderEvent
+            | ExceptionType::CoderWarning
+            | ExceptionType::CoderError
+            | ExceptionType::CoderFatalError => ExceptionCategory::Coder,
+            ExceptionType::ModuleEvent
+            | ExceptionType::ModuleWarning
+            | ExceptionType::ModuleError
+            | ExceptionType::ModuleFatalError => ExceptionCategory::Module,
+            ExceptionType::DrawEvent
+            | ExceptionType::DrawWarning
+            | ExceptionType::DrawError
+            | ExceptionType::DrawFatalError => ExceptionCategory::Draw,
+            ExceptionType::ImageEvent
+            | ExceptionType::ImageWarning
+            | ExceptionType::ImageError
+            | ExceptionType::ImageFatalError => ExceptionCategory::Image,
+            ExceptionType::WandEvent
+            | ExceptionType::WandWarning
+            | ExceptionType::WandError
+            | ExceptionType::WandFatalError => ExceptionCategory::Wand,
+            ExceptionType::TemporaryFileEvent
+            | ExceptionType::TemporaryFileWarning
+            | ExceptionType::TemporaryFileError
+            | ExceptionType::TemporaryFileFatalError => ExceptionCategory::TemporaryFile,
+            ExceptionType::TransformEvent
+            | ExceptionType::TransformWarning
+            | ExceptionType::TransformError
+            | ExceptionType::TransformFatalError => ExceptionCategory::Transform,
+            ExceptionType::XServerEvent
+            | ExceptionType::X11Event
+            | ExceptionType::XServerWarning
+            | ExceptionType::X11Warning
+            | ExceptionType::XServerError
+            | ExceptionType::X11Error
+            | ExceptionType::XServerFatalError
+            | ExceptionType::X11FatalError => ExceptionCategory::XServer,
+            ExceptionType::UserEvent
+            | ExceptionType::UserWarning
+            | ExceptionType::UserError
+            | ExceptionType::UserFatalError => ExceptionCategory::User,
+            ExceptionType::MonitorEvent
+            | ExceptionType::MonitorWarning
+            | ExceptionType::MonitorError
+            | ExceptionType::MonitorFatalError => ExceptionCategory::Monitor,
+            ExceptionType::LocaleEvent
+            | ExceptionType::LocaleWarning
+            | ExceptionType::LocaleError
+            | ExceptionType::LocaleFatalError => ExceptionCategory::Locale,
+            ExceptionType::DeprecateEvent
+            | ExceptionType::DeprecateWarning
+            | ExceptionType::DeprecateError
+            | ExceptionType::DeprecateFatalError => ExceptionCategory::Deprecate,
+            ExceptionType::RegistryEvent
+            | ExceptionType::RegistryWarning
+            | ExceptionType::RegistryError
+            | ExceptionType::RegistryFatalError => ExceptionCategory::Registry,
+            ExceptionType::ConfigureEvent
+            | ExceptionType::ConfigureWarning
+            | ExceptionType::ConfigureError
+            | ExceptionType::ConfigureFatalError => ExceptionCategory::Configure,
+        }
+    }
+
+    /// Whether the exception is caused by a transient lack of resources
+    /// (resource limits, pixel cache or temporary files), so that the same
+    /// operation may succeed if tried again later.
+    pub fn is_retryable(self) -> bool {
+        matches!(
+            self.category(),
+            ExceptionCategory::Resource
+                | ExceptionCategory::Cache
+                | ExceptionCategory::TemporaryFile
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exception_from_message() {
+        let e = Exception::from_message(
+            ExceptionType::FileOpenError,
+            "Unable to open file (/tmp/not (found).png)",
+        );
+        assert_eq!(e.get_reason(), "Unable to open file");
+        assert_eq!(e.get_description(), Some("/tmp/not (found).png"));
+
+        let e = Exception::from_message(ExceptionType::WandError, "Wand contains no images");
+        assert_eq!(e.get_reason(), "Wand contains no images");
+        assert_eq!(e.get_description(), None);
+        assert_eq!(
+            e.to_string(),
+            "kind: WandError, reason: Wand contains no images"
+        );
+    }
+
+    #[test]
+    fn test_exception_type_severity() {
+        assert_eq!(
+            ExceptionType::UndefinedException.severity(),
+            ExceptionSeverity::Undefined
+        );
+        assert_eq!(
+            ExceptionType::CacheEvent.severity(),
+            ExceptionSeverity::Event
+        );
+        assert_eq!(
+            ExceptionType::CorruptImageWarning.severity(),
+            ExceptionSeverity::Warning
+        );
+        assert_eq!(
+            ExceptionType::ErrorException.severity(),
+            ExceptionSeverity::Error
+        );
+        assert_eq!(
+            ExceptionType::ResourceFatalError.severity(),
+            ExceptionSeverity::FatalError
+        );
+        assert!(ExceptionSeverity::Warning < ExceptionSeverity::Error);
+    }
+
+    #[test]
+    fn test_exception_type_category() {
+        assert_eq!(
+            ExceptionType::CorruptImageError.category(),
+            ExceptionCategory::CorruptImage
+        );
+        assert_eq!(
+            ExceptionType::MissingDelegateWarning.category(),
+            ExceptionCategory::MissingDelegate
+        );
+        assert_eq!(
+            ExceptionType::X11Error.category(),
+            ExceptionCategory::XServer
+        );
+        assert_eq!(
+            ExceptionType::ExceptionFatalError.category(),
+            ExceptionCategory::Generic
+        );
+        assert_eq!(
+            ExceptionType::Unknown.category(),
+            ExceptionCategory::Undefined
+        );
+    }
+
+    #[test]
+    fn test_exception_type_is_retryable() {
+        assert!(ExceptionType::ResourceError.is_retryable());
+        assert!(ExceptionType::CacheError.is_retryable());
+        assert!(!ExceptionType::CorruptImageError.is_retryable());
+
+        let e: Error = io::Error::from(io::ErrorKind::TimedOut).into();
+        assert!(e.is_retryable());
+        assert_eq!(e.severity(), None);
+    }
+}
@@ -0,0 +1,1233 @@
+//! Typed reading and editing of the EXIF profile.
+//!
+//! [Exif] parses the raw `EXIF` profile of an image (a TIFF structure,
+//! optionally prefixed by `Exif\0\0`) into typed tags, and serializes them
+//! back, so that tags such as the GPS position or the orientation can be
+//! edited or removed without touching the pixels.
+//!
+//! ```no_run
+//! use graphicsmagick::{initialize, wand::MagickWand};
+//!
+//! initialize();
+//!
+//! let mut mw = MagickWand::new();
+//! mw.read_image("photo.jpg").unwrap();
+//!
+//! if let Some(exif) = mw.get_exif().unwrap() {
+//!     println!("{:?} {:?}", exif.make(), exif.model());
+//! }
+//! mw.remove_exif_gps().unwrap();
+//! mw.set_exif_orientation(1).unwrap();
+//! mw.write_image("photo-clean.jpg").unwrap();
+//! ```
+//!
+//! [Exif::to_bytes] writes the tags back in a fresh layout. Maker notes,
+//! whose content may point at absolute offsets of the original layout, are
+//! copied as opaque bytes and may not be readable by vendor tools
+//! afterwards. Thumbnails are kept only if they are JPEG compressed.
+//!
+//! The orientation and the GPS position can instead be edited in place, with
+//! [set_orientation_in_place] and [remove_gps_in_place], or their
+//! [MagickWand] counterparts, which keep every other byte of the profile.
+
+use crate::wand::MagickWand;
+use std::collections::BTreeMap;
+use thiserror::Error as ThisError;
+
+/// Name of the EXIF profile in GraphicsMagick.
+const PROFILE_NAME: &str = "EXIF";
+
+/// Prefix of the EXIF profile, before the TIFF header.
+const EXIF_PREFIX: &[u8] = b"Exif\0\0";
+
+const EXIF_IFD_POINTER: u16 = 0x8769;
+const GPS_IFD_POINTER: u16 = 0x8825;
+const INTEROP_IFD_POINTER: u16 = 0xa005;
+const THUMBNAIL_OFFSET: u16 = 0x0201;
+const THUMBNAIL_LENGTH: u16 = 0x0202;
+
+/// Image File Directory, i.e. group of tags, of the EXIF profile.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Ifd {
+    /// IFD0, tags of the main image.
+    Primary,
+    /// Camera settings.
+    Exif,
+    /// GPS position.
+    Gps,
+    /// Interoperability tags.
+    Interop,
+    /// IFD1, tags of the thumbnail.
+    Thumbnail,
+}
+
+impl Ifd {
+    /// Tags pointing at other IFDs or at the thumbnail, which are managed by
+    /// [Exif] itself.
+    fn is_structural(self, id: u16) -> bool {
+        match self {
+            Ifd::Primary => id == EXIF_IFD_POINTER || id == GPS_IFD_POINTER,
+            Ifd::Exif => id == INTEROP_IFD_POINTER,
+            Ifd::Thumbnail => id == THUMBNAIL_OFFSET || id == THUMBNAIL_LENGTH,
+            Ifd::Gps | Ifd::Interop => false,
+        }
+    }
+}
+
+/// Tag of an [Ifd].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Tag {
+    ifd: Ifd,
+    id: u16,
+}
+
+impl Tag {
+    pub const IMAGE_DESCRIPTION: Tag = Tag::new(Ifd::Primary, 0x010e);
+    pub const MAKE: Tag = Tag::new(Ifd::Primary, 0x010f);
+    pub const MODEL: Tag = Tag::new(Ifd::Primary, 0x0110);
+    pub const ORIENTATION: Tag = Tag::new(Ifd::Primary, 0x0112);
+    pub const SOFTWARE: Tag = Tag::new(Ifd::Primary, 0x0131);
+    pub const DATE_TIME: Tag = Tag::new(Ifd::Primary, 0x0132);
+    pub const ARTIST: Tag = Tag::new(Ifd::Primary, 0x013b);
+    pub const COPYRIGHT: Tag = Tag::new(Ifd::Primary, 0x8298);
+
+    pub const EXPOSURE_TIME: Tag = Tag::new(Ifd::Exif, 0x829a);
+    pub const F_NUMBER: Tag = Tag::new(Ifd::Exif, 0x829d);
+    pub const ISO_SPEED: Tag = Tag::new(Ifd::Exif, 0x8827);
+    pub const DATE_TIME_ORIGINAL: Tag = Tag::new(Ifd::Exif, 0x9003);
+    pub const DATE_TIME_DIGITIZED: Tag = Tag::new(Ifd::Exif, 0x9004);
+    pub const FOCAL_LENGTH: Tag = Tag::new(Ifd::Exif, 0x920a);
+    pub const MAKER_NOTE: Tag = Tag::new(Ifd::Exif, 0x927c);
+    pub const LENS_MODEL: Tag = Tag::new(Ifd::Exif, 0xa434);
+
+    pub const GPS_VERSION_ID: Tag = Tag::new(Ifd::Gps, 0x0000);
+    pub const GPS_LATITUDE_REF: Tag = Tag::new(Ifd::Gps, 0x0001);
+    pub const GPS_LATITUDE: Tag = Tag::new(Ifd::Gps, 0x0002);
+    pub const GPS_LONGITUDE_REF: Tag = Tag::new(Ifd::Gps, 0x0003);
+    pub const GPS_LONGITUDE: Tag = Tag::new(Ifd::Gps, 0x0004);
+    pub const GPS_ALTITUDE_REF: Tag = Tag::new(Ifd::Gps, 0x0005);
+    pub const GPS_ALTITUDE: Tag = Tag::new(Ifd::Gps, 0x0006);
+
+    /// Tag `id` of `ifd`.
+    pub const fn new(ifd: Ifd, id: u16) -> Self {
+        Tag { ifd, id }
+    }
+
+    /// IFD of the tag.
+    pub fn ifd(&self) -> Ifd {
+        self.ifd
+    }
+
+    /// Numeric id of the tag.
+    pub fn id(&self) -> u16 {
+        self.id
+    }
+}
+
+/// Byte order of the TIFF structure.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum ByteOrder {
+    /// `II`, Intel.
+    #[default]
+    LittleEndian,
+    /// `MM`, Motorola.
+    BigEndian,
+}
+
+/// Value of a tag, by TIFF type. Rationals are `(numerator, denominator)`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Byte(Vec<u8>),
+    /// Text, without the trailing NUL.
+    Ascii(String),
+    Short(Vec<u16>),
+    Long(Vec<u32>),
+    Rational(Vec<(u32, u32)>),
+    SByte(Vec<i8>),
+    Undefined(Vec<u8>),
+    SShort(Vec<i16>),
+    SLong(Vec<i32>),
+    SRational(Vec<(i32, i32)>),
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+}
+
+impl Value {
+    /// Text of an [Value::Ascii].
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Ascii(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// First element of an unsigned integer value.
+    pub fn as_u32(&self) -> Option<u32> {
+        match self {
+            Value::Byte(v) => v.first().map(|&v| v.into()),
+            Value::Short(v) => v.first().map(|&v| v.into()),
+            Value::Long(v) => v.first().copied(),
+            _ => None,
+        }
+    }
+
+    /// First element of a numeric value, `None` for a zero denominator.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Rational(v) => v.first().and_then(|&(n, d)| ratio(n.into(), d.into())),
+            Value::SRational(v) => v.first().and_then(|&(n, d)| ratio(n.into(), d.into())),
+            Value::Float(v) => v.first().map(|&v| v.into()),
+            Value::Double(v) => v.first().copied(),
+            Value::SByte(v) => v.first().map(|&v| v.into()),
+            Value::SShort(v) => v.first().map(|&v| v.into()),
+            Value::SLong(v) => v.first().map(|&v| v.into()),
+            _ => self.as_u32().map(Into::into),
+        }
+    }
+
+    /// Elements of a [Value::Rational].
+    pub fn as_rationals(&self) -> Option<&[(u32, u32)]> {
+        match self {
+            Value::Rational(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// TIFF type, element count and bytes of the value.
+    fn encode(&self, order: ByteOrder) -> (u16, u32, Vec<u8>) {
+        fn collect<T: Copy, const N: usize>(
+            values: &[T],
+            to_bytes: impl Fn(T) -> [u8; N],
+        ) -> Vec<u8> {
+            values.iter().flat_map(|&v| to_bytes(v)).collect()
+        }
+
+        let le = order == ByteOrder::LittleEndian;
+        let (kind, count, bytes) = match self {
+            Value::Byte(v) => (1, v.len(), v.clone()),
+            Value::Ascii(s) => {
+                let mut bytes = s.as_bytes().to_vec();
+                bytes.push(0);
+                (2, bytes.len(), bytes)
+            }
+            Value::Short(v) => (
+                3,
+                v.len(),
+                collect(v, |v| if le { v.to_le_bytes() } else { v.to_be_bytes() }),
+            ),
+            Value::Long(v) => (
+                4,
+                v.len(),
+                collect(v, |v| if le { v.to_le_bytes() } else { v.to_be_bytes() }),
+            ),
+            Value::Rational(v) => {
+                let flat: Vec<u32> = v.iter().flat_map(|&(n, d)| [n, d]).collect();
+                let bytes = collect(
+                    &flat,
+                    |v| if le { v.to_le_bytes() } else { v.to_be_bytes() },
+                );
+                (5, v.len(), bytes)
+            }
+            Value::SByte(v) => (6, v.len(), collect(v, i8::to_le_bytes)),
+            Value::Undefined(v) => (7, v.len(), v.clone()),
+            Value::SShort(v) => (
+                8,
+                v.len(),
+                collect(v, |v| if le { v.to_le_bytes() } else { v.to_be_bytes() }),
+            ),
+            Value::SLong(v) => (
+                9,
+                v.len(),
+                collect(v, |v| if le { v.to_le_bytes() } else { v.to_be_bytes() }),
+            ),
+            Value::SRational(v) => {
+                let flat: Vec<i32> = v.iter().flat_map(|&(n, d)| [n, d]).collect();
+                let bytes = collect(
+                    &flat,
+                    |v| if le { v.to_le_bytes() } else { v.to_be_bytes() },
+                );
+                (10, v.len(), bytes)
+            }
+            Value::Float(v) => (
+                11,
+                v.len(),
+                collect(v, |v| if le { v.to_le_bytes() } else { v.to_be_bytes() }),
+            ),
+            Value::Double(v) => (
+                12,
+                v.len(),
+                collect(v, |v| if le { v.to_le_bytes() } else { v.to_be_bytes() }),
+            ),
+        };
+        (kind, count as u32, bytes)
+    }
+}
+
+fn ratio(numerator: f64, denominator: f64) -> Option<f64> {
+    (denominator != 0.).then(|| numerator / denominator)
+}
+
+/// GPS position, in decimal degrees, and meters above sea level.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GpsPosition {
+    /// Positive to the north.
+    pub latitude: f64,
+    /// Positive to the east.
+    pub longitude: f64,
+    /// Negative below sea level.
+    pub altitude: Option<f64>,
+}
+
+/// Error returned by [Exif::parse].
+#[derive(ThisError, Debug, Clone, Eq, PartialEq)]
+#[error("invalid EXIF profile: {reason}")]
+pub struct ParseExifError {
+    reason: &'static str,
+}
+
+impl ParseExifError {
+    /// Why the profile was rejected.
+    pub fn get_reason(&self) -> &'static str {
+        self.reason
+    }
+}
+
+/// Tags of an EXIF profile.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Exif {
+    byte_order: ByteOrder,
+    fields: BTreeMap<Tag, Value>,
+    thumbnail: Option<Vec<u8>>,
+}
+
+impl Exif {
+    /// Create a profile without any tag.
+    pub fn new(byte_order: ByteOrder) -> Self {
+        Exif {
+            byte_order,
+            ..Default::default()
+        }
+    }
+
+    /// Parse a raw EXIF profile, with or without the `Exif\0\0` prefix.
+    ///
+    /// Only the TIFF header and IFD0 must be valid. Broken entries and
+    /// sub-IFDs are skipped, as cameras commonly write some.
+    pub fn parse(data: &[u8]) -> Result<Self, ParseExifError> {
+        let reader = Reader::new(data)?;
+        let mut exif = Exif::new(reader.byte_order);
+
+        let (entries, next) = reader
+            .ifd(reader.ifd0()?)
+            .ok_or_else(|| error("IFD0 out of bounds"))?;
+        let pointer = |entries: &[(u16, Value)], id| {
+            entries
+                .iter()
+                .find(|(tag, _)| *tag == id)
+                .and_then(|(_, value)| value.as_u32())
+        };
+
+        if let Some((exif_entries, _)) =
+            pointer(&entries, EXIF_IFD_POINTER).and_then(|offset| reader.ifd(offset))
+        {
+            if let Some((interop_entries, _)) =
+                pointer(&exif_entries, INTEROP_IFD_POINTER).and_then(|offset| reader.ifd(offset))
+            {
+                exif.insert_all(Ifd::Interop, interop_entries);
+            }
+            exif.insert_all(Ifd::Exif, exif_entries);
+        }
+        if let Some((gps_entries, _)) =
+            pointer(&entries, GPS_IFD_POINTER).and_then(|offset| reader.ifd(offset))
+        {
+            exif.insert_all(Ifd::Gps, gps_entries);
+        }
+        if let Some((thumbnail_entries, _)) = (next != 0).then(|| reader.ifd(next)).flatten() {
+            let offset = pointer(&thumbnail_entries, THUMBNAIL_OFFSET);
+            let length = pointer(&thumbnail_entries, THUMBNAIL_LENGTH);
+            if let Some(thumbnail) = offset
+                .zip(length)
+                .and_then(|(offset, length)| reader.bytes(offset, length as usize))
+            {
+                exif.thumbnail = Some(thumbnail.to_vec());
+                exif.insert_all(Ifd::Thumbnail, thumbnail_entries);
+            }
+        }
+        exif.insert_all(Ifd::Primary, entries);
+
+        Ok(exif)
+    }
+
+    fn insert_all(&mut self, ifd: Ifd, entries: Vec<(u16, Value)>) {
+        for (id, value) in entries {
+            if !ifd.is_structural(id) {
+                self.fields.insert(Tag::new(ifd, id), value);
+            }
+        }
+    }
+
+    /// Serialize the profile, with the `Exif\0\0` prefix, as expected by
+    /// [MagickWand::set_exif].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer {
+            buf: Vec::new(),
+            byte_order: self.byte_order,
+        };
+        match self.byte_order {
+            ByteOrder::LittleEndian => writer.buf.extend_from_slice(b"II*\0"),
+            ByteOrder::BigEndian => writer.buf.extend_from_slice(b"MM\0*"),
+        }
+        writer.buf.extend_from_slice(&writer.u32(8));
+
+        let entries = |ifd: Ifd| -> Vec<(u16, Entry<'_>)> {
+            self.fields
+                .iter()
+                .filter(|(tag, _)| tag.ifd == ifd && !ifd.is_structural(tag.id))
+                .map(|(tag, value)| (tag.id, Entry::Value(value)))
+                .collect()
+        };
+        let interop = entries(Ifd::Interop);
+        let exif = entries(Ifd::Exif);
+        let gps = entries(Ifd::Gps);
+        let has_interop = !interop.is_empty();
+        let has_exif = !exif.is_empty() || has_interop;
+        let has_gps = !gps.is_empty();
+
+        let primary = with_pointer(entries(Ifd::Primary), EXIF_IFD_POINTER, has_exif);
+        let primary = with_pointer(primary, GPS_IFD_POINTER, has_gps);
+        let ifd0 = writer.ifd(&primary);
+
+        if has_exif {
+            writer.patch(ifd0.pointer(EXIF_IFD_POINTER));
+            let exif = writer.ifd(&with_pointer(exif, INTEROP_IFD_POINTER, has_interop));
+            if has_interop {
+                writer.patch(exif.pointer(INTEROP_IFD_POINTER));
+                writer.ifd(&interop);
+            }
+        }
+        if has_gps {
+            writer.patch(ifd0.pointer(GPS_IFD_POINTER));
+            writer.ifd(&gps);
+        }
+        if let Some(thumbnail) = &self.thumbnail {
+            writer.patch(ifd0.next);
+            let length = Value::Long(vec![thumbnail.len() as u32]);
+            let mut entries = entries(Ifd::Thumbnail);
+            entries.push((THUMBNAIL_OFFSET, Entry::Pointer));
+            entries.push((THUMBNAIL_LENGTH, Entry::Value(&length)));
+            entries.sort_by_key(|(id, _)| *id);
+            let ifd1 = writer.ifd(&entries);
+            writer.patch(ifd1.pointer(THUMBNAIL_OFFSET));
+            writer.buf.extend_from_slice(thumbnail);
+        }
+
+        let mut bytes = EXIF_PREFIX.to_vec();
+        bytes.extend_from_slice(&writer.buf);
+        bytes
+    }
+
+    /// Byte order used by [Exif::to_bytes].
+    pub fn byte_order(&self) -> ByteOrder {
+        self.byte_order
+    }
+
+    /// Value of `tag`.
+    pub fn get(&self, tag: Tag) -> Option<&Value> {
+        self.fields.get(&tag)
+    }
+
+    /// Set the value of `tag`, returning the previous one.
+    ///
+    /// The tags pointing at other IFDs or at the thumbnail are written by
+    /// [Exif::to_bytes] itself, and ignored if set here.
+    pub fn set(&mut self, tag: Tag, value: Value) -> Option<Value> {
+        self.fields.insert(tag, value)
+    }
+
+    /// Remove `tag`, returning its value.
+    pub fn remove(&mut self, tag: Tag) -> Option<Value> {
+        self.fields.remove(&tag)
+    }
+
+    /// All tags, ordered by IFD then id.
+    pub fn iter(&self) -> impl Iterator<Item = (Tag, &Value)> {
+        self.fields.iter().map(|(tag, value)| (*tag, value))
+    }
+
+    /// JPEG thumbnail.
+    pub fn thumbnail(&self) -> Option<&[u8]> {
+        self.thumbnail.as_deref()
+    }
+
+    /// Remove the thumbnail, with the tags of IFD1.
+    pub fn remove_thumbnail(&mut self) {
+        self.thumbnail = None;
+        self.fields.retain(|tag, _| tag.ifd != Ifd::Thumbnail);
+    }
+
+    fn get_str(&self, tag: Tag) -> Option<&str> {
+        self.get(tag).and_then(Value::as_str)
+    }
+
+    /// Camera manufacturer.
+    pub fn make(&self) -> Option<&str> {
+        self.get_str(Tag::MAKE)
+    }
+
+    /// Camera model.
+    pub fn model(&self) -> Option<&str> {
+        self.get_str(Tag::MODEL)
+    }
+
+    /// Lens model.
+    pub fn lens_model(&self) -> Option<&str> {
+        self.get_str(Tag::LENS_MODEL)
+    }
+
+    /// Capture time, as `"YYYY:MM:DD HH:MM:SS"`.
+    pub fn date_time_original(&self) -> Option<&str> {
+        self.get_str(Tag::DATE_TIME_ORIGINAL)
+    }
+
+    /// Exposure time, in seconds.
+    pub fn exposure_time(&self) -> Option<f64> {
+        self.get(Tag::EXPOSURE_TIME).and_then(Value::as_f64)
+    }
+
+    /// F-number of the aperture.
+    pub fn f_number(&self) -> Option<f64> {
+        self.get(Tag::F_NUMBER).and_then(Value::as_f64)
+    }
+
+    /// ISO speed.
+    pub fn iso(&self) -> Option<u32> {
+        self.get(Tag::ISO_SPEED).and_then(Value::as_u32)
+    }
+
+    /// Focal length, in millimeters.
+    pub fn focal_length(&self) -> Option<f64> {
+        self.get(Tag::FOCAL_LENGTH).and_then(Value::as_f64)
+    }
+
+    /// Orientation, `1..=8`, see
+    /// [OrientationType](crate::types::OrientationType).
+    pub fn orientation(&self) -> Option<u16> {
+        self.get(Tag::ORIENTATION)
+            .and_then(Value::as_u32)
+            .map(|orientation| orientation as u16)
+    }
+
+    /// Set the orientation, `1..=8`.
+    pub fn set_orientation(&mut self, orientation: u16) {
+        self.set(Tag::ORIENTATION, Value::Short(vec![orientation]));
+    }
+
+    /// GPS position, if both latitude and longitude are set.
+    pub fn gps(&self) -> Option<GpsPosition> {
+        let coordinate = |tag, reference, negative| {
+            let [degrees, minutes, seconds] = self.get(tag)?.as_rationals()? else {
+                return None;
+            };
+            let [degrees, minutes, seconds] = [degrees, minutes, seconds]
+                .map(|&(numerator, denominator)| ratio(numerator.into(), denominator.into()));
+            let value = degrees? + minutes? / 60. + seconds? / 3600.;
+            Some(if self.get_str(reference) == Some(negative) {
+                -value
+            } else {
+                value
+            })
+        };
+        let latitude = coordinate(Tag::GPS_LATITUDE, Tag::GPS_LATITUDE_REF, "S")?;
+        let longitude = coordinate(Tag::GPS_LONGITUDE, Tag::GPS_LONGITUDE_REF, "W")?;
+        let altitude = self
+            .get(Tag::GPS_ALTITUDE)
+            .and_then(Value::as_f64)
+            .map(|altitude| {
+                if self.get(Tag::GPS_ALTITUDE_REF).and_then(Value::as_u32) == Some(1) {
+                    -altitude
+                } else {
+                    altitude
+                }
+            });
+        Some(GpsPosition {
+            latitude,
+            longitude,
+            altitude,
+        })
+    }
+
+    /// Replace the GPS tags by `position`, with a precision of a thousandth
+    /// of an arc second, and of a millimeter.
+    pub fn set_gps(&mut self, position: GpsPosition) {
+        self.remove_gps();
+
+        let dms = |value: f64| {
+            let millis = (value.abs() * 3_600_000.).round() as u64;
+            Value::Rational(vec![
+                ((millis / 3_600_000) as u32, 1),
+                ((millis / 60_000 % 60) as u32, 1),
+                ((millis % 60_000) as u32, 1000),
+            ])
+        };
+        let reference = |value: f64, positive: &str, negative: &str| {
+            Value::Ascii(if value < 0. { negative } else { positive }.to_owned())
+        };
+
+        self.set(Tag::GPS_VERSION_ID, Value::Byte(vec![2, 3, 0, 0]));
+        self.set(
+            Tag::GPS_LATITUDE_REF,
+            reference(position.latitude, "N", "S"),
+        );
+        self.set(Tag::GPS_LATITUDE, dms(position.latitude));
+        self.set(
+            Tag::GPS_LONGITUDE_REF,
+            reference(position.longitude, "E", "W"),
+        );
+        self.set(Tag::GPS_LONGITUDE, dms(position.longitude));
+        if let Some(altitude) = position.altitude {
+            self.set(
+                Tag::GPS_ALTITUDE_REF,
+                Value::Byte(vec![u8::from(altitude < 0.)]),
+            );
+            self.set(
+                Tag::GPS_ALTITUDE,
+                Value::Rational(vec![((altitude.abs() * 1000.).round() as u32, 1000)]),
+            );
+        }
+    }
+
+    /// Remove every tag of the GPS IFD.
+    pub fn remove_gps(&mut self) {
+        self.fields.retain(|tag, _| tag.ifd != Ifd::Gps);
+    }
+}
+
+fn error(reason: &'static str) -> ParseExifError {
+    ParseExifError { reason }
+}
+
+/// Set the orientation of the raw EXIF `profile`, `1..=8`, by overwriting
+/// the value of its orientation tag, so that the rest of the profile is kept
+/// byte for byte.
+///
+/// # Return
+///
+/// `false`, leaving `profile` unchanged, if it has no valid orientation tag
+/// made of a single SHORT to overwrite.
+pub fn set_orientation_in_place(
+    profile: &mut [u8],
+    orientation: u16,
+) -> Result<bool, ParseExifError> {
+    let reader = Reader::new(profile)?;
+    let Some(entry) = reader.find_entry(reader.ifd0()?, Tag::ORIENTATION.id) else {
+        return Ok(false);
+    };
+    let Some((3, offset, 2)) = reader
+        .entry_data(entry)
+        .filter(|&(_, offset, len)| reader.bytes(offset, len).is_some())
+    else {
+        return Ok(false);
+    };
+    let value = match reader.byte_order {
+        ByteOrder::LittleEndian => orientation.to_le_bytes(),
+        ByteOrder::BigEndian => orientation.to_be_bytes(),
+    };
+    let offset = reader.start + offset as usize;
+    profile[offset..offset + 2].copy_from_slice(&value);
+    Ok(true)
+}
+
+/// Remove the GPS position from the raw EXIF `profile` without changing its
+/// layout: the GPS IFD and the values it points at are zeroed, and its
+/// pointer is removed from IFD0.
+///
+/// # Return
+///
+/// `false`, leaving `profile` unchanged, if it has no GPS IFD.
+pub fn remove_gps_in_place(profile: &mut [u8]) -> Result<bool, ParseExifError> {
+    let reader = Reader::new(profile)?;
+    let ifd0 = reader.ifd0()?;
+    let Some(pointer) = reader.find_entry(ifd0, GPS_IFD_POINTER) else {
+        return Ok(false);
+    };
+    let count = reader
+        .u16(ifd0)
+        .ok_or_else(|| error("IFD0 out of bounds"))?;
+    let ifd0_len = 2 + usize::from(count) * 12 + 4;
+    reader
+        .bytes(ifd0, ifd0_len)
+        .ok_or_else(|| error("IFD0 out of bounds"))?;
+
+    // Zero the values first, then the IFD itself.
+    let mut blank = Vec::new();
+    if let Some(gps) = reader.u32(pointer + 8) {
+        let count = reader.u16(gps).unwrap_or(0);
+        for i in 0..u32::from(count) {
+            if let Some((_, offset, len)) = reader.entry_data(gps + 2 + i * 12) {
+                if len > 4 {
+                    blank.push((offset, len));
+                }
+            }
+        }
+        blank.push((gps, 2 + usize::from(count) * 12 + 4));
+    }
+    let count = match reader.byte_order {
+        ByteOrder::LittleEndian => (count - 1).to_le_bytes(),
+        ByteOrder::BigEndian => (count - 1).to_be_bytes(),
+    };
+    let (start, data_len) = (reader.start, reader.data.len());
+    for (offset, len) in blank {
+        let offset = offset as usize;
+        let end = offset.saturating_add(len).min(data_len);
+        if offset < end {
+            profile[start + offset..start + end].fill(0);
+        }
+    }
+
+    // Shift the following entries, and the offset of the next IFD, over the
+    // pointer.
+    let (ifd0, pointer) = (start + ifd0 as usize, start + pointer as usize);
+    let end = ifd0 + ifd0_len;
+    profile.copy_within(pointer + 12..end, pointer);
+    profile[end - 12..end].fill(0);
+    profile[ifd0..ifd0 + 2].copy_from_slice(&count);
+    Ok(true)
+}
+
+/// Bounds checked reads of a TIFF structure.
+struct Reader<'d> {
+    /// TIFF structure, after the `Exif\0\0` prefix if any.
+    data: &'d [u8],
+    byte_order: ByteOrder,
+    /// Length of the prefix.
+    start: usize,
+}
+
+impl<'d> Reader<'d> {
+    /// Reader of a raw EXIF profile, with or without the `Exif\0\0` prefix.
+    fn new(profile: &'d [u8]) -> Result<Self, ParseExifError> {
+        let data = profile.strip_prefix(EXIF_PREFIX).unwrap_or(profile);
+        let byte_order = match data.get(..4) {
+            Some(b"II*\0") => ByteOrder::LittleEndian,
+            Some(b"MM\0*") => ByteOrder::BigEndian,
+            _ => return Err(error("missing TIFF header")),
+        };
+        Ok(Reader {
+            data,
+            byte_order,
+            start: profile.len() - data.len(),
+        })
+    }
+
+    /// Offset of IFD0.
+    fn ifd0(&self) -> Result<u32, ParseExifError> {
+        self.u32(4).ok_or_else(|| error("missing IFD0 offset"))
+    }
+
+    fn bytes(&self, offset: u32, len: usize) -> Option<&'d [u8]> {
+        let offset = usize::try_from(offset).ok()?;
+        self.data.get(offset..offset.checked_add(len)?)
+    }
+
+    fn array<const N: usize>(&self, offset: u32) -> Option<[u8; N]> {
+        self.bytes(offset, N)?.try_into().ok()
+    }
+
+    fn u16(&self, offset: u32) -> Option<u16> {
+        let bytes = self.array(offset)?;
+        Some(match self.byte_order {
+            ByteOrder::LittleEndian => u16::from_le_bytes(bytes),
+            ByteOrder::BigEndian => u16::from_be_bytes(bytes),
+        })
+    }
+
+    fn u32(&self, offset: u32) -> Option<u32> {
+        let bytes = self.array(offset)?;
+        Some(match self.byte_order {
+            ByteOrder::LittleEndian => u32::from_le_bytes(bytes),
+            ByteOrder::BigEndian => u32::from_be_bytes(bytes),
+        })
+    }
+
+    /// Entries of the IFD at `offset`, and the offset of the next IFD.
+    fn ifd(&self, offset: u32) -> Option<(Vec<(u16, Value)>, u32)> {
+        let count = self.u16(offset)?;
+        let entries = (0..u32::from(count))
+            .filter_map(|i| self.entry(offset + 2 + i * 12))
+            .collect();
+        let next = self.u32(offset + 2 + u32::from(count) * 12).unwrap_or(0);
+        Some((entries, next))
+    }
+
+    /// Offset of the entry `id` of the IFD at `offset`.
+    fn find_entry(&self, offset: u32, id: u16) -> Option<u32> {
+        let count = self.u16(offset)?;
+        (0..u32::from(count))
+            .map(|i| offset + 2 + i * 12)
+            .find(|&entry| self.u16(entry) == Some(id))
+    }
+
+    /// Type, offset and length of the value of the entry at `offset`.
+    fn entry_data(&self, offset: u32) -> Option<(u16, u32, usize)> {
+        let kind = self.u16(offset + 2)?;
+        let count = self.u32(offset + 4)? as usize;
+        let size = match kind {
+            1 | 2 | 6 | 7 => 1,
+            3 | 8 => 2,
+            4 | 9 | 11 => 4,
+            5 | 10 | 12 => 8,
+            _ => return None,
+        };
+        let len = count.checked_mul(size)?;
+        let data_offset = if len <= 4 {
+            offset + 8
+        } else {
+            self.u32(offset + 8)?
+        };
+        Some((kind, data_offset, len))
+    }
+
+    fn entry(&self, offset: u32) -> Option<(u16, Value)> {
+        let id = self.u16(offset)?;
+        let (kind, data_offset, len) = self.entry_data(offset)?;
+        let data = self.bytes(data_offset, len)?;
+
+        let le = self.byte_order == ByteOrder::LittleEndian;
+        let u16s = || -> Vec<u16> {
+            data.chunks_exact(2)
+                .map(|b| {
+                    let b = [b[0], b[1]];
+                    if le {
+                        u16::from_le_bytes(b)
+                    } else {
+                        u16::from_be_bytes(b)
+                    }
+                })
+                .collect()
+        };
+        let u32s = || -> Vec<u32> {
+            data.chunks_exact(4)
+                .map(|b| {
+                    let b = [b[0], b[1], b[2], b[3]];
+                    if le {
+                        u32::from_le_bytes(b)
+                    } else {
+                        u32::from_be_bytes(b)
+                    }
+                })
+                .collect()
+        };
+        let u64s = || -> Vec<u64> {
+            data.chunks_exact(8)
+                .map(|b| {
+                    let b: [u8; 8] = b.try_into().unwrap();
+                    if le {
+                        u64::from_le_bytes(b)
+                    } else {
+                        u64::from_be_bytes(b)
+                    }
+                })
+                .collect()
+        };
+
+        let value = match kind {
+            1 => Value::Byte(data.to_vec()),
+            2 => {
+                let text = data.strip_suffix(b"\0").unwrap_or(data);
+                Value::Ascii(String::from_utf8_lossy(text).into_owned())
+            }
+            3 => Value::Short(u16s()),
+            4 => Value::Long(u32s()),
+            5 => Value::Rational(u32s().chunks_exact(2).map(|v| (v[0], v[1])).collect()),
+            6 => Value::SByte(data.iter().map(|&b| b as i8).collect()),
+            7 => Value::Undefined(data.to_vec()),
+            8 => Value::SShort(u16s().into_iter().map(|v| v as i16).collect()),
+            9 => Value::SLong(u32s().into_iter().map(|v| v as i32).collect()),
+            10 => Value::SRational(
+                u32s()
+                    .chunks_exact(2)
+                    .map(|v| (v[0] as i32, v[1] as i32))
+                    .collect(),
+            ),
+            11 => Value::Float(u32s().into_iter().map(f32::from_bits).collect()),
+            12 => Value::Double(u64s().into_iter().map(f64::from_bits).collect()),
+            _ => unreachable!(),
+        };
+        Some((id, value))
+    }
+}
+
+/// Entry written by [Writer::ifd].
+enum Entry<'v> {
+    Value(&'v Value),
+    /// Offset patched later with [Writer::patch].
+    Pointer,
+}
+
+/// Add a [Entry::Pointer] `id` to `entries` if `present`.
+fn with_pointer(
+    mut entries: Vec<(u16, Entry<'_>)>,
+    id: u16,
+    present: bool,
+) -> Vec<(u16, Entry<'_>)> {
+    if present {
+        entries.push((id, Entry::Pointer));
+        entries.sort_by_key(|(id, _)| *id);
+    }
+    entries
+}
+
+/// Positions of the offsets of an IFD written by [Writer::ifd], to be
+/// patched once the pointed data is written.
+struct WrittenIfd {
+    pointers: Vec<(u16, usize)>,
+    next: usize,
+}
+
+impl WrittenIfd {
+    fn pointer(&self, id: u16) -> usize {
+        self.pointers
+            .iter()
+            .find(|(pointer, _)| *pointer == id)
+            .map(|(_, position)| *position)
+            .expect("pointer entry was written")
+    }
+}
+
+struct Writer {
+    buf: Vec<u8>,
+    byte_order: ByteOrder,
+}
+
+impl Writer {
+    fn u16(&self, value: u16) -> [u8; 2] {
+        match self.byte_order {
+            ByteOrder::LittleEndian => value.to_le_bytes(),
+            ByteOrder::BigEndian => value.to_be_bytes(),
+        }
+    }
+
+    fn u32(&self, value: u32) -> [u8; 4] {
+        match self.byte_order {
+            ByteOrder::LittleEndian => value.to_le_bytes(),
+            ByteOrder::BigEndian => value.to_be_bytes(),
+        }
+    }
+
+    /// Pad to a word boundary, as TIFF offsets must be even.
+    fn align(&mut self) {
+        if self.buf.len() % 2 == 1 {
+            self.buf.push(0);
+        }
+    }
+
+    /// Write the offset of the end of the buffer at `position`.
+    fn patch(&mut self, position: usize) {
+        self.align();
+        let offset = self.u32(self.buf.len() as u32);
+        self.buf[position..position + 4].copy_from_slice(&offset);
+    }
+
+    /// Write an IFD, sorted by id, followed by the values that don't fit in
+    /// their entry.
+    fn ifd(&mut self, entries: &[(u16, Entry<'_>)]) -> WrittenIfd {
+        self.align();
+        let start = self.buf.len();
+        let count = self.u16(entries.len() as u16);
+        self.buf.extend_from_slice(&count);
+        self.buf.resize(start + 2 + entries.len() * 12 + 4, 0);
+
+        let mut pointers = Vec::new();
+        for (i, (id, entry)) in entries.iter().enumerate() {
+            let position = start + 2 + i * 12;
+            let (kind, count, bytes) = match entry {
+                Entry::Value(value) => value.encode(self.byte_order),
+                Entry::Pointer => {
+                    pointers.push((*id, position + 8));
+                    (4, 1, vec![0; 4])
+                }
+            };
+            let header = [self.u16(*id), self.u16(kind)].concat();
+            self.buf[position..position + 4].copy_from_slice(&header);
+            let count = self.u32(count);
+            self.buf[position + 4..position + 8].copy_from_slice(&count);
+            if bytes.len() <= 4 {
+                self.buf[position + 8..position + 8 + bytes.len()].copy_from_slice(&bytes);
+            } else {
+                self.patch(position + 8);
+                self.buf.extend_from_slice(&bytes);
+            }
+        }
+
+        WrittenIfd {
+            pointers,
+            next: start + 2 + entries.len() * 12,
+        }
+    }
+}
+
+impl MagickWand<'_> {
+    /// Parse the `EXIF` profile of the image, see [Exif].
+    ///
+    /// # Return
+    ///
+    /// `None` if the image has no EXIF profile, an error if the wand has no
+    /// image.
+    pub fn get_exif(&mut self) -> crate::Result<Option<Exif>> {
+        self.check_has_images()?;
+        match self.get_image_profile_bytes(PROFILE_NAME) {
+            Some(profile) => Ok(Some(Exif::parse(&profile)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Replace the `EXIF` profile of the image by `exif`.
+    pub fn set_exif(&mut self, exif: &Exif) -> crate::Result<&mut Self> {
+        self.set_image_profile_bytes(PROFILE_NAME, &exif.to_bytes())
+    }
+
+    /// Set the orientation of the `EXIF` profile of the image, `1..=8`, in
+    /// place, see [set_orientation_in_place]. A profile without orientation
+    /// tag to overwrite is re-encoded with [Exif::to_bytes], unless
+    /// `orientation` is 1, the default. An image without EXIF profile is left
+    /// unchanged, a wand without image is an error.
+    pub fn set_exif_orientation(&mut self, orientation: u16) -> crate::Result<&mut Self> {
+        self.check_has_images()?;
+        let Some(profile) = self.get_image_profile_bytes(PROFILE_NAME) else {
+            return Ok(self);
+        };
+        let mut profile = profile.to_vec();
+        if set_orientation_in_place(&mut profile, orientation)? {
+            return self.set_image_profile_bytes(PROFILE_NAME, &profile);
+        }
+        let mut exif = Exif::parse(&profile)?;
+        if exif.orientation().unwrap_or(1) == orientation {
+            return Ok(self);
+        }
+        exif.set_orientation(orientation);
+        self.set_exif(&exif)
+    }
+
+    /// Remove the GPS position from the `EXIF` profile of the image, in
+    /// place, see [remove_gps_in_place]. An image without EXIF profile is
+    /// left unchanged, a wand without image is an error.
+    pub fn remove_exif_gps(&mut self) -> crate::Result<&mut Self> {
+        self.check_has_images()?;
+        let Some(profile) = self.get_image_profile_bytes(PROFILE_NAME) else {
+            return Ok(self);
+        };
+        let mut profile = profile.to_vec();
+        if remove_gps_in_place(&mut profile)? {
+            self.set_image_profile_bytes(PROFILE_NAME, &profile)?;
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::new_logo_magick_wand;
+
+    fn new_exif(byte_order: ByteOrder) -> Exif {
+        let mut exif = Exif::new(byte_order);
+        exif.set(Tag::MAKE, Value::Ascii("Canon".to_owned()));
+        exif.set(Tag::MODEL, Value::Ascii("EOS 5D".to_owned()));
+        exif.set_orientation(6);
+        exif.set(
+            Tag::DATE_TIME_ORIGINAL,
+            Value::Ascii("2024:05:01 12:34:56".to_owned()),
+        );
+        exif.set(Tag::EXPOSURE_TIME, Value::Rational(vec![(1, 250)]));
+        exif.set(Tag::F_NUMBER, Value::Rational(vec![(28, 10)]));
+        exif.set(Tag::ISO_SPEED, Value::Short(vec![400]));
+        exif.set(Tag::MAKER_NOTE, Value::Undefined(vec![1, 2, 3, 4, 5]));
+        exif.set(
+            Tag::new(Ifd::Interop, 0x0001),
+            Value::Ascii("R98".to_owned()),
+        );
+        exif.set_gps(GpsPosition {
+            latitude: 48.858222,
+            longitude: -2.2945,
+            altitude: Some(35.5),
+        });
+        exif
+    }
+
+    #[test]
+    fn test_exif_round_trip() {
+        for byte_order in [ByteOrder::LittleEndian, ByteOrder::BigEndian] {
+            let exif = new_exif(byte_order);
+            let bytes = exif.to_bytes();
+            assert!(bytes.starts_with(EXIF_PREFIX));
+
+            let parsed = Exif::parse(&bytes).unwrap();
+            assert_eq!(parsed, exif);
+            // Also without the prefix.
+            assert_eq!(Exif::parse(&bytes[EXIF_PREFIX.len()..]).unwrap(), exif);
+
+            assert_eq!(parsed.make(), Some("Canon"));
+            assert_eq!(parsed.model(), Some("EOS 5D"));
+            assert_eq!(parsed.orientation(), Some(6));
+            assert_eq!(parsed.date_time_original(), Some("2024:05:01 12:34:56"));
+            assert_eq!(parsed.exposure_time(), Some(0.004));
+            assert_eq!(parsed.f_number(), Some(2.8));
+            assert_eq!(parsed.iso(), Some(400));
+        }
+    }
+
+    #[test]
+    fn test_exif_gps() {
+        let mut exif = new_exif(ByteOrder::LittleEndian);
+        let gps = exif.gps().unwrap();
+        assert!((gps.latitude - 48.858222).abs() < 1e-6);
+        assert!((gps.longitude + 2.2945).abs() < 1e-6);
+        assert_eq!(gps.altitude, Some(35.5));
+        assert_eq!(exif.get_str(Tag::GPS_LONGITUDE_REF), Some("W"));
+
+        exif.remove_gps();
+        assert_eq!(exif.gps(), None);
+        let parsed = Exif::parse(&exif.to_bytes()).unwrap();
+        assert!(parsed.iter().all(|(tag, _)| tag.ifd() != Ifd::Gps));
+        assert_eq!(parsed.make(), Some("Canon"));
+    }
+
+    #[test]
+    fn test_exif_thumbnail() {
+        let mut exif = new_exif(ByteOrder::BigEndian);
+        exif.thumbnail = Some(vec![0xff, 0xd8, 0xff, 0xd9]);
+        exif.set(Tag::new(Ifd::Thumbnail, 0x0103), Value::Short(vec![6]));
+
+        let mut parsed = Exif::parse(&exif.to_bytes()).unwrap();
+        assert_eq!(parsed.thumbnail(), Some(&[0xff, 0xd8, 0xff, 0xd9][..]));
+        assert_eq!(parsed, exif);
+
+        parsed.remove_thumbnail();
+        let parsed = Exif::parse(&parsed.to_bytes()).unwrap();
+        assert_eq!(parsed.thumbnail(), None);
+        assert!(parsed.get(Tag::new(Ifd::Thumbnail, 0x0103)).is_none());
+    }
+
+    #[test]
+    fn test_exif_parse_invalid() {
+        assert!(Exif::parse(b"").is_err());
+        assert!(Exif::parse(b"Exif\0\0XX*\0\x08\0\0\0").is_err());
+        assert!(Exif::parse(b"II*\0\xff\0\0\0").is_err());
+
+        // A broken entry is skipped.
+        let mut bytes = b"II*\0\x08\0\0\0\x02\0".to_vec();
+        // Orientation, SHORT, 1, 6.
+        bytes.extend_from_slice(&[0x12, 0x01, 3, 0, 1, 0, 0, 0, 6, 0, 0, 0]);
+        // Make, ASCII, 100 bytes out of bounds.
+        bytes.extend_from_slice(&[0x0f, 0x01, 2, 0, 100, 0, 0, 0, 0xff, 0, 0, 0]);
+        bytes.extend_from_slice(&[0, 0, 0, 0]);
+        let exif = Exif::parse(&bytes).unwrap();
+        assert_eq!(exif.orientation(), Some(6));
+        assert_eq!(exif.make(), None);
+    }
+
+    #[test]
+    fn test_exif_in_place() {
+        for byte_order in [ByteOrder::LittleEndian, ByteOrder::BigEndian] {
+            let exif = new_exif(byte_order);
+            let mut bytes = exif.to_bytes();
+            let len = bytes.len();
+
+            assert!(set_orientation_in_place(&mut bytes, 3).unwrap());
+            assert!(remove_gps_in_place(&mut bytes).unwrap());
+            assert!(!remove_gps_in_place(&mut bytes).unwrap());
+            assert_eq!(bytes.len(), len);
+
+            let parsed = Exif::parse(&bytes).unwrap();
+            assert_eq!(parsed.orientation(), Some(3));
+            assert!(parsed.iter().all(|(tag, _)| tag.ifd() != Ifd::Gps));
+            let mut expected = exif.clone();
+            expected.set_orientation(3);
+            expected.remove_gps();
+            assert_eq!(parsed, expected);
+
+            // The coordinates themselves are erased.
+            let latitude = exif.get(Tag::GPS_LATITUDE).unwrap().encode(byte_order).2;
+            assert!(!bytes.windows(latitude.len()).any(|w| w == latitude));
+        }
+
+        let mut bytes = Exif::new(ByteOrder::LittleEndian).to_bytes();
+        let before = bytes.clone();
+        assert!(!set_orientation_in_place(&mut bytes, 6).unwrap());
+        assert!(!remove_gps_in_place(&mut bytes).unwrap());
+        assert_eq!(bytes, before);
+        assert!(set_orientation_in_place(&mut b"garbage".to_vec(), 1).is_err());
+    }
+
+    #[test]
+    fn test_exif_jpeg_round_trip() {
+        let read_jpeg = |mw: &mut MagickWand<'_>| {
+            mw.set_image_format("JPEG").unwrap();
+            let jpeg = mw.write_image_blob().unwrap();
+            let mut mw = MagickWand::new();
+            mw.read_image_transient_blob(&jpeg).unwrap();
+            mw
+        };
+
+        let mut mw = new_logo_magick_wand();
+        let exif = new_exif(ByteOrder::BigEndian);
+        mw.set_exif(&exif).unwrap();
+        let mut mw = read_jpeg(&mut mw);
+        assert_eq!(mw.get_exif().unwrap(), Some(exif.clone()));
+
+        let profile = mw.get_image_profile_bytes(PROFILE_NAME).unwrap().to_vec();
+        mw.set_exif_orientation(1).unwrap();
+        mw.remove_exif_gps().unwrap();
+        let edited = mw.get_image_profile_bytes(PROFILE_NAME).unwrap().to_vec();
+        assert_eq!(edited.len(), profile.len());
+
+        let mut mw = read_jpeg(&mut mw);
+        let parsed = mw.get_exif().unwrap().unwrap();
+        assert_eq!(parsed.orientation(), Some(1));
+        assert_eq!(parsed.gps(), None);
+        assert_eq!(parsed.make(), Some("Canon"));
+        assert_eq!(parsed.get(Tag::MAKER_NOTE), exif.get(Tag::MAKER_NOTE));
+        assert_eq!(
+            mw.get_image_profile_bytes(PROFILE_NAME).unwrap().to_vec(),
+            edited
+        );
+    }
+
+    #[test]
+    fn test_exif_set_orientation_re_encode() {
+        let mut mw = new_logo_magick_wand();
+        mw.set_exif_orientation(6).unwrap();
+        assert_eq!(mw.get_exif().unwrap(), None);
+
+        let mut exif = Exif::new(ByteOrder::LittleEndian);
+        exif.set(Tag::MAKE, Value::Ascii("Canon".to_owned()));
+        mw.set_exif(&exif).unwrap();
+        mw.set_exif_orientation(6).unwrap();
+        let parsed = mw.get_exif().unwrap().unwrap();
+        assert_eq!(parsed.orientation(), Some(6));
+        assert_eq!(parsed.make(), Some("Canon"));
+    }
+
+    #[test]
+    fn test_exif_magick_wand() {
+        let mut mw = new_logo_magick_wand();
+        assert_eq!(mw.get_exif().unwrap(), None);
+
+        let exif = new_exif(ByteOrder::LittleEndian);
+        mw.set_exif(&exif).unwrap();
+        assert_eq!(mw.get_exif().unwrap(), Some(exif));
+
+        mw.set_image_profile_bytes(PROFILE_NAME, b"garbage")
+            .unwrap();
+        assert!(matches!(mw.get_exif(), Err(crate::Error::Exif(_))));
+    }
+
+    #[test]
+    fn test_exif_magick_wand_no_image() {
+        crate::initialize();
+        let mut mw = MagickWand::new();
+        assert!(mw.get_exif().is_err());
+        assert!(mw.set_exif_orientation(1).is_err());
+        assert!(mw.remove_exif_gps().is_err());
+    }
+}
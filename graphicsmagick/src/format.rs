@@ -0,0 +1,287 @@
+//! Formats supported by the linked GraphicsMagick, with their capabilities.
+//!
+//! Unlike [MagickWand::query_formats](crate::wand::MagickWand::query_formats),
+//! which only lists names, [FormatInfo] tells whether a format can be read
+//! and written, and how.
+//!
+//! ```no_run
+//! use graphicsmagick::{format::FormatInfo, initialize};
+//!
+//! initialize();
+//!
+//! let accepted: Vec<_> = FormatInfo::list()
+//!     .unwrap()
+//!     .into_iter()
+//!     .filter(|format| format.can_read && format.blob_support)
+//!     .filter_map(|format| format.mime_type)
+//!     .collect();
+//! println!("Accept: {}", accepted.join(", "));
+//!
+//! let jpeg = FormatInfo::from_extension("jpg").unwrap();
+//! assert!(jpeg.can_write);
+//! ```
+
+use crate::{
+    error::{Exception, with_exception_info},
+    utils::{MagickAutoRelinquish, assert_initialized},
+};
+use graphicsmagick_sys::{GetMagickInfo, GetMagickInfoArray, MagickInfo};
+use null_terminated_str::IntoNullTerminatedString;
+use std::{ffi::CStr, os::raw::c_char};
+
+/// MIME types of the common formats, GraphicsMagick doesn't record them.
+const MIME_TYPES: &[(&str, &str)] = &[
+    ("AVIF", "image/avif"),
+    ("BMP", "image/bmp"),
+    ("DCM", "application/dicom"),
+    ("EPS", "application/postscript"),
+    ("GIF", "image/gif"),
+    ("HEIC", "image/heic"),
+    ("HEIF", "image/heif"),
+    ("ICO", "image/x-icon"),
+    ("JNG", "image/x-jng"),
+    ("JP2", "image/jp2"),
+    ("JPEG", "image/jpeg"),
+    ("JPG", "image/jpeg"),
+    ("JXL", "image/jxl"),
+    ("MNG", "video/x-mng"),
+    ("PBM", "image/x-portable-bitmap"),
+    ("PCX", "image/x-pcx"),
+    ("PDF", "application/pdf"),
+    ("PGM", "image/x-portable-graymap"),
+    ("PNG", "image/png"),
+    ("PNM", "image/x-portable-anymap"),
+    ("PPM", "image/x-portable-pixmap"),
+    ("PS", "application/postscript"),
+    ("PSD", "image/vnd.adobe.photoshop"),
+    ("SVG", "image/svg+xml"),
+    ("TGA", "image/x-tga"),
+    ("TIF", "image/tiff"),
+    ("TIFF", "image/tiff"),
+    ("WEBP", "image/webp"),
+    ("WMF", "image/wmf"),
+    ("XBM", "image/x-xbitmap"),
+    ("XPM", "image/x-xpixmap"),
+];
+
+/// MIME type of the format `name`, case insensitive.
+fn mime_type(name: &str) -> Option<&'static str> {
+    MIME_TYPES
+        .iter()
+        .find(|(format, _)| format.eq_ignore_ascii_case(name))
+        .map(|(_, mime_type)| *mime_type)
+}
+
+/// Formats of the common file extensions, which aren't always the name of
+/// the format, e.g. `jpg` for `JPEG`.
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("avif", "AVIF"),
+    ("bmp", "BMP"),
+    ("dcm", "DCM"),
+    ("eps", "EPS"),
+    ("gif", "GIF"),
+    ("heic", "HEIC"),
+    ("heif", "HEIF"),
+    ("ico", "ICO"),
+    ("jng", "JNG"),
+    ("jp2", "JP2"),
+    ("jpe", "JPEG"),
+    ("jpeg", "JPEG"),
+    ("jpg", "JPEG"),
+    ("jxl", "JXL"),
+    ("mng", "MNG"),
+    ("pbm", "PBM"),
+    ("pcx", "PCX"),
+    ("pdf", "PDF"),
+    ("pgm", "PGM"),
+    ("png", "PNG"),
+    ("pnm", "PNM"),
+    ("ppm", "PPM"),
+    ("ps", "PS"),
+    ("psd", "PSD"),
+    ("svg", "SVG"),
+    ("tga", "TGA"),
+    ("tif", "TIFF"),
+    ("tiff", "TIFF"),
+    ("webp", "WEBP"),
+    ("wmf", "WMF"),
+    ("xbm", "XBM"),
+    ("xpm", "XPM"),
+];
+
+/// Format of the files with `extension`, without the leading dot, case
+/// insensitive.
+fn extension_format(extension: &str) -> Option<&'static str> {
+    let extension = extension.to_ascii_lowercase();
+    EXTENSIONS
+        .binary_search_by(|(known, _)| known.cmp(&extension.as_str()))
+        .ok()
+        .map(|i| EXTENSIONS[i].1)
+}
+
+/// Format, or coder, registered in GraphicsMagick's `MagickInfo` list.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct FormatInfo {
+    /// Name, e.g. `"PNG"`, as given to
+    /// [MagickWand::set_image_format](crate::wand::MagickWand::set_image_format).
+    pub name: String,
+    /// Description, e.g. `"Portable Network Graphics"`.
+    pub description: String,
+    /// MIME type, for the common formats only.
+    pub mime_type: Option<&'static str>,
+    /// Whether images of this format can be read.
+    pub can_read: bool,
+    /// Whether images of this format can be written.
+    pub can_write: bool,
+    /// Whether a file of this format can hold several frames, e.g. an
+    /// animation.
+    pub multi_frame: bool,
+    /// Whether images of this format can be read from and written to memory,
+    /// e.g. by [MagickWand::read_image_blob](crate::wand::MagickWand::read_image_blob),
+    /// without a temporary file.
+    pub blob_support: bool,
+}
+
+impl FormatInfo {
+    /// Every format, sorted by name, but the internal ones.
+    pub fn list() -> crate::Result<Vec<FormatInfo>> {
+        assert_initialized();
+        with_exception_info(|exception| unsafe {
+            let array = GetMagickInfoArray(exception);
+            let Some(_free) = MagickAutoRelinquish::new(array.cast()) else {
+                return Err(Exception::from_exception_info(exception).into());
+            };
+            let mut formats = Vec::new();
+            for i in 0.. {
+                let Some(info) = (*array.add(i)).as_ref() else {
+                    break;
+                };
+                if info.stealth == 0 {
+                    formats.push(FormatInfo::new(info));
+                }
+            }
+            Ok(formats)
+        })
+    }
+
+    /// Format named `name`, case insensitive, `None` if unknown.
+    pub fn from_name<'s>(name: impl IntoNullTerminatedString<'s>) -> Option<FormatInfo> {
+        assert_initialized();
+        let name = name.into_null_terminated_string();
+        // `GetMagickInfo` returns the head of the coder list for these,
+        // rather than a format of that name.
+        if matches!(&**name, "" | "*") {
+            return None;
+        }
+        with_exception_info(|exception| unsafe {
+            GetMagickInfo(name.as_ptr(), exception)
+                .as_ref()
+                .map(|info| FormatInfo::new(info))
+        })
+    }
+
+    /// Format of the files with `extension`, with or without the leading dot,
+    /// e.g. `"jpg"` or `".JPG"`.
+    ///
+    /// Only the extensions of the common formats, those with a
+    /// [FormatInfo::mime_type], are known, as most format names, such as the
+    /// pseudo formats `XC` or `*`, aren't file extensions.
+    ///
+    /// # Return
+    ///
+    /// `None` if the extension is unknown, or the linked GraphicsMagick
+    /// doesn't support its format.
+    pub fn from_extension(extension: &str) -> Option<FormatInfo> {
+        let extension = extension.strip_prefix('.').unwrap_or(extension);
+        FormatInfo::from_name(extension_format(extension)?)
+    }
+
+    /// # Safety
+    ///
+    /// The strings of `info` must be null or valid.
+    unsafe fn new(info: &MagickInfo) -> Self {
+        let to_string = |s: *const c_char| {
+            if s.is_null() {
+                String::new()
+            } else {
+                unsafe { CStr::from_ptr(s) }.to_string_lossy().into_owned()
+            }
+        };
+        let name = to_string(info.name);
+        FormatInfo {
+            mime_type: mime_type(&name),
+            description: to_string(info.description),
+            can_read: info.decoder.is_some(),
+            can_write: info.encoder.is_some(),
+            multi_frame: info.adjoin != 0,
+            blob_support: info.blob_support != 0,
+            name,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::initialize;
+
+    #[test]
+    fn test_format_mime_type() {
+        assert_eq!(mime_type("PNG"), Some("image/png"));
+        assert_eq!(mime_type("jpg"), Some("image/jpeg"));
+        assert_eq!(mime_type("XC"), None);
+        assert!(MIME_TYPES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn test_format_extension_format() {
+        assert_eq!(extension_format("jpg"), Some("JPEG"));
+        assert_eq!(extension_format("TIF"), Some("TIFF"));
+        assert_eq!(extension_format("*"), None);
+        assert_eq!(extension_format("xc"), None);
+        assert!(EXTENSIONS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        // Every format has a MIME type.
+        assert!(
+            EXTENSIONS
+                .iter()
+                .all(|(_, format)| mime_type(format).is_some())
+        );
+    }
+
+    #[test]
+    fn test_format_info_list() {
+        initialize();
+        let formats = FormatInfo::list().unwrap();
+        assert!(formats.windows(2).all(|pair| pair[0].name <= pair[1].name));
+
+        let png = formats.iter().find(|format| format.name == "PNG").unwrap();
+        assert!(png.can_read && png.can_write && png.blob_support);
+        assert!(!png.multi_frame);
+        assert_eq!(png.mime_type, Some("image/png"));
+        assert!(!png.description.is_empty());
+
+        let gif = formats.iter().find(|format| format.name == "GIF").unwrap();
+        assert!(gif.multi_frame);
+    }
+
+    #[test]
+    fn test_format_info_lookup() {
+        initialize();
+        let webp = FormatInfo::from_name("webp").unwrap();
+        assert_eq!(webp.name, "WEBP");
+        assert_eq!(webp.mime_type, Some("image/webp"));
+
+        let jpeg = FormatInfo::from_extension(".jpg").unwrap();
+        assert!(jpeg.can_read && jpeg.can_write);
+        assert_eq!(jpeg.mime_type, Some("image/jpeg"));
+        assert_eq!(FormatInfo::from_extension("JPEG").unwrap().name, "JPEG");
+
+        assert_eq!(FormatInfo::from_name("NO-SUCH-FORMAT"), None);
+        assert_eq!(FormatInfo::from_name("*"), None);
+        assert_eq!(FormatInfo::from_name(""), None);
+        assert_eq!(FormatInfo::from_extension(""), None);
+        assert_eq!(FormatInfo::from_extension("tar.gz"), None);
+        assert_eq!(FormatInfo::from_extension("*"), None);
+        assert_eq!(FormatInfo::from_extension(".xc"), None);
+    }
+}
@@ -0,0 +1,459 @@
+//! Typed GraphicsMagick geometry, such as `"100x100+10+10!"`.
+//!
+//! <http://www.graphicsmagick.org/GraphicsMagick.html#details-geometry>
+//!
+//! A [Geometry] can be passed wherever the crate takes a geometry string,
+//! e.g. [MagickWand::transform_image](crate::wand::MagickWand::transform_image):
+//!
+//! ```no_run
+//! use graphicsmagick::{geometry::Geometry, initialize, wand::MagickWand};
+//!
+//! initialize();
+//!
+//! let mut mw = MagickWand::new();
+//! mw.read_image("input.png").unwrap();
+//!
+//! let geometry: Geometry = "640x480>".parse().unwrap();
+//! assert_eq!(geometry.dimensions(1280, 720), (640, 360));
+//! let resized = mw.transform_image("", geometry).unwrap();
+//! ```
+
+use null_terminated_str::{IntoNullTerminatedString, NullTerminatedStr, NullTerminatedString};
+use std::{
+    borrow::Cow,
+    fmt,
+    os::raw::{c_double, c_long, c_ulong},
+    str::FromStr,
+};
+use thiserror::Error as ThisError;
+
+/// Flags of a [Geometry], written after the size and offset.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct GeometryFlags {
+    /// `%`, the width and height are percentages of the image size.
+    pub percent: bool,
+    /// `@`, the width (times the height, if any) is an area in pixels.
+    pub area: bool,
+    /// `!`, ignore the aspect ratio.
+    pub force: bool,
+    /// `^`, fill the width and height instead of fitting in them.
+    pub fill: bool,
+    /// `>`, only shrink images larger than the geometry.
+    pub shrink_only: bool,
+    /// `<`, only enlarge images smaller than the geometry.
+    pub enlarge_only: bool,
+}
+
+/// Parsed geometry: `[width][x height][{+-}x{+-}y][flags]`.
+///
+/// [Display](fmt::Display) writes it back in the syntax of GraphicsMagick,
+/// so that `geometry.to_string().parse()` gives the same geometry.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Geometry {
+    /// Width, in pixels, percent or area depending on the flags.
+    pub width: Option<c_double>,
+    /// Height, in pixels or percent depending on the flags.
+    pub height: Option<c_double>,
+    /// Offset `(x, y)` of the region.
+    pub offset: Option<(c_long, c_long)>,
+    /// Flags, written after the size and offset.
+    pub flags: GeometryFlags,
+}
+
+impl Geometry {
+    /// Fit in `width` x `height` pixels, keeping the aspect ratio.
+    pub fn new(width: c_ulong, height: c_ulong) -> Self {
+        Geometry {
+            width: Some(width as c_double),
+            height: Some(height as c_double),
+            ..Default::default()
+        }
+    }
+
+    /// Scale by `width` and `height` percent.
+    pub fn percent(width: c_double, height: c_double) -> Self {
+        Geometry {
+            width: Some(width),
+            height: Some(height),
+            flags: GeometryFlags {
+                percent: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Scale to an area of `pixels`, keeping the aspect ratio.
+    pub fn area(pixels: c_ulong) -> Self {
+        Geometry {
+            width: Some(pixels as c_double),
+            flags: GeometryFlags {
+                area: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Set the offset.
+    pub fn with_offset(mut self, x: c_long, y: c_long) -> Self {
+        self.offset = Some((x, y));
+        self
+    }
+
+    /// Set the `!` flag.
+    pub fn force(mut self) -> Self {
+        self.flags.force = true;
+        self
+    }
+
+    /// Set the `^` flag.
+    pub fn fill(mut self) -> Self {
+        self.flags.fill = true;
+        self
+    }
+
+    /// Set the `>` flag.
+    pub fn shrink_only(mut self) -> Self {
+        self.flags.shrink_only = true;
+        self
+    }
+
+    /// Set the `<` flag.
+    pub fn enlarge_only(mut self) -> Self {
+        self.flags.enlarge_only = true;
+        self
+    }
+
+    /// Size of an image of `width` x `height` pixels, once resized with this
+    /// geometry. The offset is ignored.
+    pub fn dimensions(&self, width: c_ulong, height: c_ulong) -> (c_ulong, c_ulong) {
+        if width == 0 || height == 0 {
+            return (width, height);
+        }
+        let (source_width, source_height) = (width as c_double, height as c_double);
+
+        let (target_width, target_height) = if self.flags.percent {
+            let scale_x = self.width.or(self.height).unwrap_or(100.) / 100.;
+            let scale_y = self.height.unwrap_or(scale_x * 100.) / 100.;
+            (source_width * scale_x, source_height * scale_y)
+        } else if self.flags.area {
+            let area = match (self.width, self.height) {
+                (Some(width), Some(height)) => width * height,
+                (Some(area), None) | (None, Some(area)) => area,
+                (None, None) => return (width, height),
+            };
+            let scale = (area / (source_width * source_height)).sqrt();
+            (
+                (source_width * scale).floor(),
+                (source_height * scale).floor(),
+            )
+        } else {
+            match (self.width, self.height) {
+                (None, None) => return (width, height),
+                (Some(width), Some(height)) if self.flags.force => (width, height),
+                (width, height) => {
+                    let scale_x = width.map(|width| width / source_width);
+                    let scale_y = height.map(|height| height / source_height);
+                    let scale = match (scale_x, scale_y) {
+                        (Some(x), Some(y)) if self.flags.fill => x.max(y),
+                        (Some(x), Some(y)) => x.min(y),
+                        (Some(scale), None) | (None, Some(scale)) => scale,
+                        (None, None) => unreachable!(),
+                    };
+                    (source_width * scale, source_height * scale)
+                }
+            }
+        };
+
+        let target_width = (target_width.round() as c_ulong).max(1);
+        let target_height = (target_height.round() as c_ulong).max(1);
+        if self.flags.shrink_only && target_width >= width && target_height >= height {
+            return (width, height);
+        }
+        if self.flags.enlarge_only && target_width <= width && target_height <= height {
+            return (width, height);
+        }
+        (target_width, target_height)
+    }
+}
+
+impl fmt::Display for Geometry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(width) = self.width {
+            write!(f, "{}", width)?;
+        }
+        if let Some(height) = self.height {
+            write!(f, "x{}", height)?;
+        }
+        if let Some((x, y)) = self.offset {
+            write!(f, "{:+}{:+}", x, y)?;
+        }
+        let flags = [
+            (self.flags.percent, '%'),
+            (self.flags.area, '@'),
+            (self.flags.force, '!'),
+            (self.flags.fill, '^'),
+            (self.flags.shrink_only, '>'),
+            (self.flags.enlarge_only, '<'),
+        ];
+        for (_, flag) in flags.iter().filter(|(set, _)| *set) {
+            write!(f, "{}", flag)?;
+        }
+        Ok(())
+    }
+}
+
+/// Error returned when parsing a [Geometry].
+#[derive(ThisError, Debug, Clone, Eq, PartialEq)]
+#[error("invalid geometry {input:?}: {reason}")]
+pub struct ParseGeometryError {
+    input: String,
+    reason: &'static str,
+}
+
+impl ParseGeometryError {
+    /// The string that failed to parse.
+    pub fn get_input(&self) -> &str {
+        &self.input
+    }
+
+    /// Why the string was rejected.
+    pub fn get_reason(&self) -> &'static str {
+        self.reason
+    }
+}
+
+/// Cursor over the size and offset part of a geometry string.
+struct Parser<'s> {
+    rest: &'s str,
+}
+
+impl Parser<'_> {
+    fn eat(&mut self, c: char) -> bool {
+        match self.rest.strip_prefix(c) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &str {
+        let end = self.rest.find(|c| !f(c)).unwrap_or(self.rest.len());
+        let (taken, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        taken
+    }
+
+    fn size(&mut self) -> Result<Option<c_double>, &'static str> {
+        let digits = self.take_while(|c| c.is_ascii_digit() || c == '.');
+        if digits.is_empty() {
+            return Ok(None);
+        }
+        digits
+            .parse()
+            .map(Some)
+            .map_err(|_| "width and height must be numbers")
+    }
+
+    fn offset(&mut self) -> Result<Option<c_long>, &'static str> {
+        let negative = if self.eat('+') {
+            false
+        } else if self.eat('-') {
+            true
+        } else {
+            return Ok(None);
+        };
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        let offset: c_long = digits.parse().map_err(|_| "offsets must be integers")?;
+        Ok(Some(if negative { -offset } else { offset }))
+    }
+}
+
+impl FromStr for Geometry {
+    type Err = ParseGeometryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = |reason| ParseGeometryError {
+            input: s.to_owned(),
+            reason,
+        };
+
+        // Like GraphicsMagick, accept the flags anywhere.
+        let mut flags = GeometryFlags::default();
+        let mut rest = String::with_capacity(s.len());
+        for c in s.trim().chars() {
+            match c {
+                '%' => flags.percent = true,
+                '@' => flags.area = true,
+                '!' => flags.force = true,
+                '^' => flags.fill = true,
+                '>' => flags.shrink_only = true,
+                '<' => flags.enlarge_only = true,
+                c => rest.push(c),
+            }
+        }
+
+        let mut parser = Parser { rest: &rest };
+        let width = parser.size().map_err(error)?;
+        let height = if parser.eat('x') || parser.eat('X') {
+            parser.size().map_err(error)?
+        } else {
+            None
+        };
+        let offset = match parser.offset().map_err(error)? {
+            Some(x) => Some((x, parser.offset().map_err(error)?.unwrap_or(0))),
+            None => None,
+        };
+        if !parser.rest.is_empty() {
+            return Err(error("unexpected trailing characters"));
+        }
+        if width.is_none() && height.is_none() && offset.is_none() {
+            return Err(error("missing width, height or offset"));
+        }
+
+        Ok(Geometry {
+            width,
+            height,
+            offset,
+            flags,
+        })
+    }
+}
+
+impl<'a> IntoNullTerminatedString<'a> for Geometry {
+    fn into_null_terminated_string(self) -> Cow<'a, NullTerminatedStr> {
+        Cow::Owned(NullTerminatedString::from(self.to_string()))
+    }
+}
+
+impl<'a> IntoNullTerminatedString<'a> for &Geometry {
+    fn into_null_terminated_string(self) -> Cow<'a, NullTerminatedStr> {
+        Cow::Owned(NullTerminatedString::from(self.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::new_logo_magick_wand, types::MontageMode, wand::DrawingWand};
+
+    #[test]
+    fn test_geometry_parse() {
+        let geometry: Geometry = "100x50+10-5^!".parse().unwrap();
+        assert_eq!(geometry.width, Some(100.));
+        assert_eq!(geometry.height, Some(50.));
+        assert_eq!(geometry.offset, Some((10, -5)));
+        assert!(geometry.flags.fill && geometry.flags.force);
+        assert!(!geometry.flags.percent);
+
+        let geometry: Geometry = "x50".parse().unwrap();
+        assert_eq!((geometry.width, geometry.height), (None, Some(50.)));
+
+        let geometry: Geometry = "50%".parse().unwrap();
+        assert_eq!(
+            geometry,
+            Geometry {
+                height: None,
+                ..Geometry::percent(50., 50.)
+            }
+        );
+
+        let geometry: Geometry = ">640X480".parse().unwrap();
+        assert_eq!(geometry, Geometry::new(640, 480).shrink_only());
+
+        assert_eq!("10000@".parse::<Geometry>().unwrap(), Geometry::area(10000));
+        assert_eq!("+3+4".parse::<Geometry>().unwrap().offset, Some((3, 4)));
+
+        for invalid in [
+            "",
+            "!",
+            "abc",
+            "100x50+",
+            "100y50",
+            "1.2.3x4",
+            "10x10+1.5+2",
+        ] {
+            let err = invalid.parse::<Geometry>().unwrap_err();
+            assert_eq!(err.get_input(), invalid);
+        }
+    }
+
+    #[test]
+    fn test_geometry_display() {
+        for s in [
+            "100x50",
+            "100",
+            "x50",
+            "100x50+10-5",
+            "50%",
+            "10000@",
+            "100x50!^><",
+            "1.5x2.5%",
+        ] {
+            let geometry: Geometry = s.parse().unwrap();
+            assert_eq!(geometry.to_string(), s);
+            assert_eq!(geometry.to_string().parse::<Geometry>().unwrap(), geometry);
+        }
+        assert_eq!(
+            Geometry::new(64, 32).with_offset(-1, 2).fill().to_string(),
+            "64x32-1+2^"
+        );
+    }
+
+    #[test]
+    fn test_geometry_dimensions() {
+        let dimensions =
+            |s: &str, width, height| s.parse::<Geometry>().unwrap().dimensions(width, height);
+
+        assert_eq!(dimensions("100x100", 200, 100), (100, 50));
+        assert_eq!(dimensions("100x100^", 200, 100), (200, 100));
+        assert_eq!(dimensions("100x100!", 200, 100), (100, 100));
+        assert_eq!(dimensions("100", 200, 100), (100, 50));
+        assert_eq!(dimensions("x25", 200, 100), (50, 25));
+        assert_eq!(dimensions("50%", 200, 100), (100, 50));
+        assert_eq!(dimensions("50x200%", 200, 100), (100, 200));
+        assert_eq!(dimensions("5000@", 200, 100), (100, 50));
+        assert_eq!(dimensions("400x400>", 200, 100), (200, 100));
+        assert_eq!(dimensions("100x100>", 200, 100), (100, 50));
+        assert_eq!(dimensions("100x100<", 200, 100), (200, 100));
+        assert_eq!(dimensions("400x400<", 200, 100), (400, 200));
+        assert_eq!(dimensions("+10+10", 200, 100), (200, 100));
+    }
+
+    #[test]
+    fn test_geometry_transform_image() {
+        let mut mw = new_logo_magick_wand();
+        let geometry = Geometry::new(100, 50);
+        let mut transformed = mw.transform_image("", geometry).unwrap();
+        assert_eq!(
+            (
+                transformed.get_image_width(),
+                transformed.get_image_height()
+            ),
+            geometry.dimensions(1024, 1024)
+        );
+
+        let mut mw = new_logo_magick_wand();
+        let mut cropped = mw
+            .transform_image(Geometry::new(10, 20).with_offset(5, 5), "")
+            .unwrap();
+        assert_eq!(cropped.get_image_width(), 10);
+        assert_eq!(cropped.get_image_height(), 20);
+    }
+
+    #[test]
+    fn test_geometry_montage_image() {
+        let mut mw = new_logo_magick_wand();
+        mw.montage_image(
+            &DrawingWand::new(),
+            Geometry::new(1, 1),
+            Geometry::new(64, 64).with_offset(2, 2),
+            MontageMode::ConcatenateMode,
+            "",
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,256 @@
+//! Conversions between [MagickWand] and [`image::DynamicImage`].
+
+use super::new_canvas;
+use crate::{
+    types::{ColorspaceType, ImageType},
+    wand::{
+        MagickWand,
+        magick::{MagickWandExportType, MagickWandImportSlice},
+    },
+};
+use ::image::{DynamicImage, ImageBuffer, Luma, LumaA, Pixel, Rgb, Rgba};
+use std::os::raw::c_ulong;
+
+/// Export the current image of `wand` as an [ImageBuffer], in the channel
+/// order specified by `map`.
+fn export<P>(
+    wand: &mut MagickWand<'_>,
+    map: &str,
+) -> crate::Result<ImageBuffer<P, Vec<P::Subpixel>>>
+where
+    P: Pixel,
+    P::Subpixel: MagickWandExportType,
+{
+    let columns = wand.get_image_width();
+    let rows = wand.get_image_height();
+    let pixels = wand.get_image_pixels::<P::Subpixel>(0, 0, columns, rows, map)?;
+
+    let too_large = || crate::Error::Conversion(format!("image of {columns}x{rows} is too large"));
+    let width = u32::try_from(columns).map_err(|_| too_large())?;
+    let height = u32::try_from(rows).map_err(|_| too_large())?;
+    ImageBuffer::from_raw(width, height, pixels).ok_or_else(too_large)
+}
+
+/// Create a wand with a single image holding `buffer`, in the channel order
+/// specified by `map`.
+fn import<'a, P>(
+    buffer: &ImageBuffer<P, Vec<P::Subpixel>>,
+    map: &str,
+    depth: c_ulong,
+) -> crate::Result<MagickWand<'a>>
+where
+    P: Pixel,
+    P::Subpixel: MagickWandExportType,
+{
+    let (width, height) = buffer.dimensions();
+    let (columns, rows) = (c_ulong::from(width), c_ulong::from(height));
+
+    let mut wand = new_canvas(columns, rows, map, depth)?;
+
+    let import =
+        MagickWandImportSlice::new(columns, rows, map, buffer.as_raw()).ok_or_else(|| {
+            crate::Error::Conversion("image buffer is smaller than its dimensions".to_owned())
+        })?;
+    wand.set_image_pixels(0, 0, import)?;
+    Ok(wand)
+}
+
+/// Export the current image of the wand.
+///
+/// Grayscale images become `Luma` or `LumaA`, other images `Rgb` or `Rgba`,
+/// depending on whether they have an opacity channel. CMYK images are
+/// exported from a copy transformed to RGB. Images with a depth greater than
+/// 8 bits become 16-bit images.
+#[cfg_attr(docsrs, doc(cfg(feature = "image")))]
+impl TryFrom<&mut MagickWand<'_>> for DynamicImage {
+    type Error = crate::Error;
+
+    fn try_from(wand: &mut MagickWand<'_>) -> crate::Result<Self> {
+        let image_type = wand.get_image_type();
+        if matches!(
+            image_type,
+            ImageType::ColorSeparationType | ImageType::ColorSeparationMatteType
+        ) {
+            // The red, green and blue channels of a CMYK image hold its cyan,
+            // magenta and yellow.
+            let Some(mut rgb) = wand.get_image() else {
+                return Err(unsafe { wand.get_error() });
+            };
+            rgb.set_image_colorspace(ColorspaceType::RGBColorspace)?;
+            return DynamicImage::try_from(&mut rgb);
+        }
+
+        let sixteen_bits = wand.get_image_depth() > 8;
+        let image = match (image_type, sixteen_bits) {
+            (ImageType::BilevelType | ImageType::GrayscaleType, false) => {
+                DynamicImage::ImageLuma8(export::<Luma<u8>>(wand, "I")?)
+            }
+            (ImageType::BilevelType | ImageType::GrayscaleType, true) => {
+                DynamicImage::ImageLuma16(export::<Luma<u16>>(wand, "I")?)
+            }
+            (ImageType::GrayscaleMatteType, false) => {
+                DynamicImage::ImageLumaA8(export::<LumaA<u8>>(wand, "IA")?)
+            }
+            (ImageType::GrayscaleMatteType, true) => {
+                DynamicImage::ImageLumaA16(export::<LumaA<u16>>(wand, "IA")?)
+            }
+            (ImageType::PaletteMatteType | ImageType::TrueColorMatteType, false) => {
+                DynamicImage::ImageRgba8(export::<Rgba<u8>>(wand, "RGBA")?)
+            }
+            (ImageType::PaletteMatteType | ImageType::TrueColorMatteType, true) => {
+                DynamicImage::ImageRgba16(export::<Rgba<u16>>(wand, "RGBA")?)
+            }
+            (_, false) => DynamicImage::ImageRgb8(export::<Rgb<u8>>(wand, "RGB")?),
+            (_, true) => DynamicImage::ImageRgb16(export::<Rgb<u16>>(wand, "RGB")?),
+        };
+        Ok(image)
+    }
+}
+
+/// Create a wand with a single image, of depth 8 or 16 bits like `image`.
+///
+/// 8-bit and 16-bit `Luma`, `LumaA`, `Rgb` and `Rgba` images are supported.
+///
+/// # Panic
+///
+/// Panic if [`initialize`](crate::initialize) hasn't been called.
+#[cfg_attr(docsrs, doc(cfg(feature = "image")))]
+impl TryFrom<&DynamicImage> for MagickWand<'_> {
+    type Error = crate::Error;
+
+    fn try_from(image: &DynamicImage) -> crate::Result<Self> {
+        match image {
+            DynamicImage::ImageLuma8(buffer) => import(buffer, "I", 8),
+            DynamicImage::ImageLumaA8(buffer) => import(buffer, "IA", 8),
+            DynamicImage::ImageRgb8(buffer) => import(buffer, "RGB", 8),
+            DynamicImage::ImageRgba8(buffer) => import(buffer, "RGBA", 8),
+            DynamicImage::ImageLuma16(buffer) => import(buffer, "I", 16),
+            DynamicImage::ImageLumaA16(buffer) => import(buffer, "IA", 16),
+            DynamicImage::ImageRgb16(buffer) => import(buffer, "RGB", 16),
+            DynamicImage::ImageRgba16(buffer) => import(buffer, "RGBA", 16),
+            image => Err(crate::Error::Conversion(format!(
+                "unsupported color type {:?}",
+                image.color()
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{initialize, tests::new_logo_magick_wand, types::Quantum};
+    use ::image::{ColorType, GenericImageView};
+
+    #[test]
+    fn test_dynamic_image_try_from_magick_wand() {
+        let mut mw = new_logo_magick_wand();
+        let image = DynamicImage::try_from(&mut mw).unwrap();
+        assert_eq!(image.dimensions(), (1024, 1024));
+        assert!(matches!(image.color(), ColorType::Rgb8 | ColorType::Rgba8));
+
+        let map = if image.color().has_alpha() {
+            "RGBA"
+        } else {
+            "RGB"
+        };
+        let pixels = mw.get_image_pixels::<u8>(0, 0, 1024, 1024, map).unwrap();
+        assert_eq!(image.as_bytes(), pixels.as_slice());
+    }
+
+    #[test]
+    fn test_dynamic_image_try_from_cmyk_magick_wand() {
+        initialize();
+        let image = DynamicImage::ImageRgb8(ImageBuffer::from_fn(16, 8, |x, y| {
+            Rgb([(x * 16) as u8, (y * 32) as u8, 200])
+        }));
+        let mut mw = MagickWand::try_from(&image).unwrap();
+        mw.set_image_colorspace(ColorspaceType::CMYKColorspace)
+            .unwrap();
+        assert_eq!(mw.get_image_type(), ImageType::ColorSeparationType);
+
+        let round_trip = DynamicImage::try_from(&mut mw).unwrap();
+        assert_eq!(round_trip.color(), ColorType::Rgb8);
+        for (expected, actual) in image.as_bytes().iter().zip(round_trip.as_bytes()) {
+            assert!(expected.abs_diff(*actual) <= 1, "{expected} != {actual}");
+        }
+        // The wand itself is left in CMYK.
+        assert_eq!(mw.get_image_type(), ImageType::ColorSeparationType);
+    }
+
+    #[test]
+    fn test_magick_wand_try_from_dynamic_image() {
+        initialize();
+
+        let images = [
+            DynamicImage::ImageLuma8(ImageBuffer::from_fn(16, 8, |x, y| {
+                Luma([(x * 16 + y) as u8])
+            })),
+            DynamicImage::ImageLumaA8(ImageBuffer::from_fn(16, 8, |x, y| {
+                LumaA([(x * 16) as u8, (y * 32) as u8])
+            })),
+            DynamicImage::ImageRgb8(ImageBuffer::from_fn(16, 8, |x, y| {
+                Rgb([(x * 16) as u8, (y * 32) as u8, 7])
+            })),
+            DynamicImage::ImageRgba8(ImageBuffer::from_fn(16, 8, |x, y| {
+                Rgba([(x * 16) as u8, (y * 32) as u8, 7, 128])
+            })),
+            DynamicImage::ImageLuma16(ImageBuffer::from_fn(16, 8, |x, y| {
+                Luma([(x * 4096 + y) as u16])
+            })),
+            DynamicImage::ImageLumaA16(ImageBuffer::from_fn(16, 8, |x, y| {
+                LumaA([(x * 4096) as u16, (y * 8192) as u16])
+            })),
+            DynamicImage::ImageRgb16(ImageBuffer::from_fn(16, 8, |x, y| {
+                Rgb([(x * 4096) as u16, (y * 8192) as u16, 7])
+            })),
+            DynamicImage::ImageRgba16(ImageBuffer::from_fn(16, 8, |x, y| {
+                Rgba([(x * 4096) as u16, (y * 8192) as u16, 7, 32768])
+            })),
+        ];
+
+        for image in images {
+            let mut mw = MagickWand::try_from(&image).unwrap();
+            assert_eq!(mw.get_image_width(), 16);
+            assert_eq!(mw.get_image_height(), 8);
+
+            let round_trip = DynamicImage::try_from(&mut mw).unwrap();
+            assert_eq!(round_trip.color(), image.color());
+            if image.color().bytes_per_pixel() / image.color().channel_count() == 1 {
+                assert_eq!(round_trip.as_bytes(), image.as_bytes());
+            } else {
+                // A GraphicsMagick built with 8-bit quantums keeps 8 bits of
+                // each channel, but a depth or byte order bug goes far beyond.
+                let tolerance = if u32::from(Quantum::MAX) >= u32::from(u16::MAX) {
+                    0
+                } else {
+                    128
+                };
+                let (expected, actual) = (image.to_rgba16(), round_trip.to_rgba16());
+                for (expected, actual) in expected.pixels().zip(actual.pixels()) {
+                    assert!(
+                        expected
+                            .0
+                            .iter()
+                            .zip(actual.0)
+                            .all(|(expected, actual)| expected.abs_diff(actual) <= tolerance),
+                        "{:?}: {:?} != {:?}",
+                        image.color(),
+                        expected,
+                        actual
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_magick_wand_try_from_dynamic_image_unsupported() {
+        initialize();
+        let image = DynamicImage::new_rgb32f(2, 2);
+        assert!(matches!(
+            MagickWand::try_from(&image),
+            Err(crate::Error::Conversion(_))
+        ));
+    }
+}
@@ -0,0 +1,32 @@
+//! Conversions between [MagickWand] and the types of other crates, each
+//! behind the cargo feature of the same name.
+
+use crate::wand::MagickWand;
+use std::os::raw::c_ulong;
+
+#[cfg(feature = "image")]
+mod image;
+#[cfg(feature = "ndarray")]
+mod ndarray;
+
+/// Create a wand holding a single `columns`x`rows` image of `depth` bits, to
+/// be filled by [`MagickWand::set_image_pixels`] with the channels of `map`.
+///
+/// The image has an opacity channel if `map` has an alpha or opacity channel.
+fn new_canvas<'a>(
+    columns: c_ulong,
+    rows: c_ulong,
+    map: &str,
+    depth: c_ulong,
+) -> crate::Result<MagickWand<'a>> {
+    let mut wand = MagickWand::new();
+    wand.set_size(columns, rows)?;
+    let color = if map.contains(['A', 'O']) {
+        "xc:transparent"
+    } else {
+        "xc:black"
+    };
+    wand.read_image(color)?;
+    wand.set_image_depth(depth)?;
+    Ok(wand)
+}
@@ -0,0 +1,126 @@
+//! Conversions between [MagickWand] and [`ndarray`] arrays.
+
+use super::new_canvas;
+use crate::wand::{
+    MagickWand,
+    magick::{MagickWandExportType, MagickWandImportSlice},
+};
+use ::ndarray::{Array3, ArrayView3};
+use null_terminated_str::IntoNullTerminatedString;
+use std::{mem, os::raw::c_ulong};
+
+impl MagickWand<'_> {
+    /// Export the current image as an array of shape `(rows, columns,
+    ///
+    /// map.len())`, i.e. height × width × channels, with the channels in the
+    ///
+    /// order specified by `map` (as [`MagickWand::get_image_pixels`]).
+    ///
+    #[cfg_attr(docsrs, doc(cfg(feature = "ndarray")))]
+    pub fn to_ndarray<'s, T: MagickWandExportType>(
+        &mut self,
+        map: impl IntoNullTerminatedString<'s>,
+    ) -> crate::Result<Array3<T>> {
+        let map = map.into_null_terminated_string();
+        let columns = self.get_image_width();
+        let rows = self.get_image_height();
+        let pixels = self.get_image_pixels::<T>(0, 0, columns, rows, &*map)?;
+        Array3::from_shape_vec((rows as usize, columns as usize, map.len()), pixels)
+            .map_err(|e| crate::Error::Conversion(e.to_string()))
+    }
+
+    /// Create a wand with a single image from an array of shape `(rows,
+    ///
+    /// columns, map.len())`, i.e. height × width × channels, with the channels
+    ///
+    /// in the order specified by `map` (as [`MagickWand::set_image_pixels`]).
+    ///
+    /// The image has a depth of 8 bits for [`u8`] arrays, 16 bits otherwise.
+    ///
+    /// # Panic
+    ///
+    /// Panic if [`initialize`](crate::initialize) hasn't been called.
+    ///
+    #[cfg_attr(docsrs, doc(cfg(feature = "ndarray")))]
+    pub fn from_ndarray<'s, T: MagickWandExportType + Clone>(
+        array: &ArrayView3<'_, T>,
+        map: impl IntoNullTerminatedString<'s>,
+    ) -> crate::Result<Self> {
+        let map = map.into_null_terminated_string();
+        let (rows, columns, channels) = array.dim();
+        if channels != map.len() {
+            return Err(crate::Error::Conversion(format!(
+                "array has {} channels but map {:?} has {}",
+                channels,
+                &*map,
+                map.len()
+            )));
+        }
+
+        let (columns, rows) = (columns as c_ulong, rows as c_ulong);
+        let depth = if mem::size_of::<T>() == 1 { 8 } else { 16 };
+        let mut wand = new_canvas(columns, rows, &map, depth)?;
+
+        let array = array.as_standard_layout();
+        let pixels = array.as_slice().expect("array is in standard layout");
+        let import = MagickWandImportSlice::new(columns, rows, &*map, pixels)
+            .expect("array has exactly rows * columns * channels elements");
+        wand.set_image_pixels(0, 0, import)?;
+        Ok(wand)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{initialize, tests::new_logo_magick_wand};
+    use ::ndarray::{Array3, Axis, s};
+
+    #[test]
+    fn test_magick_wand_to_ndarray() {
+        let mut mw = new_logo_magick_wand();
+        let array = mw.to_ndarray::<u8>("RGBA").unwrap();
+        assert_eq!(array.dim(), (1024, 1024, 4));
+
+        // Channels follow the map.
+        let pixels = mw.get_image_pixels::<u8>(5, 3, 1, 1, "BGR").unwrap();
+        let bgr = mw.to_ndarray::<u8>("BGR").unwrap();
+        assert_eq!(bgr.slice(s![3, 5, ..]).to_vec(), pixels);
+        assert_eq!(array[[3, 5, 0]], pixels[2]);
+        assert_eq!(array[[3, 5, 2]], pixels[0]);
+    }
+
+    #[test]
+    fn test_magick_wand_from_ndarray() {
+        initialize();
+
+        let array = Array3::from_shape_fn((8, 16, 3), |(y, x, c)| (y * 16 + x + c) as u8);
+        let mut mw = MagickWand::from_ndarray(&array.view(), "RGB").unwrap();
+        assert_eq!(mw.get_image_width(), 16);
+        assert_eq!(mw.get_image_height(), 8);
+        assert_eq!(mw.to_ndarray::<u8>("RGB").unwrap(), array);
+
+        // Not in standard layout.
+        let strided = array.slice(s![.., ..;2, ..]);
+        let mut mw = MagickWand::from_ndarray(&strided, "BGR").unwrap();
+        assert_eq!(mw.get_image_width(), 8);
+        assert_eq!(mw.get_image_height(), 8);
+        assert_eq!(mw.to_ndarray::<u8>("BGR").unwrap(), strided);
+
+        let floats = Array3::from_elem((2, 2, 1), 0.5f32);
+        let mut mw = MagickWand::from_ndarray(&floats.view(), "I").unwrap();
+        let gray = mw.to_ndarray::<f32>("I").unwrap();
+        assert!(gray.iter().all(|&i| (i - 0.5).abs() < 1e-3));
+        assert_eq!(gray.len_of(Axis(2)), 1);
+    }
+
+    #[test]
+    fn test_magick_wand_from_ndarray_channel_mismatch() {
+        initialize();
+        let array = Array3::<u8>::zeros((2, 2, 3));
+        assert!(matches!(
+            MagickWand::from_ndarray(&array.view(), "RGBA"),
+            Err(crate::Error::Conversion(_))
+        ));
+    }
+}
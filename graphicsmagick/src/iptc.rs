@@ -0,0 +1,575 @@
+//! Typed reading and editing of the IPTC profile.
+//!
+//! [Iptc] parses the IPTC-IIM datasets of the `IPTC` (or `8BIM`) profile of
+//! an image, which GraphicsMagick keeps as Photoshop image resources, and
+//! writes them back, leaving the other image resources untouched.
+//!
+//! ```no_run
+//! use graphicsmagick::{initialize, iptc::Iptc, wand::MagickWand};
+//!
+//! initialize();
+//!
+//! let mut mw = MagickWand::new();
+//! mw.read_image("photo.jpg").unwrap();
+//!
+//! let mut iptc = mw.get_iptc().unwrap().unwrap_or_default();
+//! iptc.set_caption("Sunset over the harbour");
+//! iptc.set_keywords(["sunset", "harbour"]);
+//! mw.set_iptc(&iptc).unwrap();
+//! mw.write_image("photo-tagged.jpg").unwrap();
+//! ```
+
+use crate::wand::MagickWand;
+use std::borrow::Cow;
+use thiserror::Error as ThisError;
+
+/// Prefix GraphicsMagick may keep in front of the image resources.
+const PHOTOSHOP_PREFIX: &[u8] = b"Photoshop 3.0\0";
+
+/// Signature of a Photoshop image resource.
+const RESOURCE_SIGNATURE: &[u8] = b"8BIM";
+
+/// Id of the image resource holding the IPTC-IIM datasets.
+const IPTC_RESOURCE_ID: u16 = 0x0404;
+
+/// Tag marker starting every IPTC-IIM dataset.
+const TAG_MARKER: u8 = 0x1c;
+
+/// `ESC % G`, the value of [Tag::CODED_CHARACTER_SET] for UTF-8.
+const UTF8_CHARACTER_SET: &[u8] = b"\x1b%G";
+
+/// IPTC-IIM dataset, identified by its record and dataset numbers.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Tag {
+    record: u8,
+    dataset: u8,
+}
+
+impl Tag {
+    pub const CODED_CHARACTER_SET: Tag = Tag::new(1, 90);
+
+    pub const RECORD_VERSION: Tag = Tag::new(2, 0);
+    pub const OBJECT_NAME: Tag = Tag::new(2, 5);
+    pub const KEYWORDS: Tag = Tag::new(2, 25);
+    pub const SPECIAL_INSTRUCTIONS: Tag = Tag::new(2, 40);
+    pub const DATE_CREATED: Tag = Tag::new(2, 55);
+    pub const TIME_CREATED: Tag = Tag::new(2, 60);
+    pub const BY_LINE: Tag = Tag::new(2, 80);
+    pub const BY_LINE_TITLE: Tag = Tag::new(2, 85);
+    pub const CITY: Tag = Tag::new(2, 90);
+    pub const PROVINCE_STATE: Tag = Tag::new(2, 95);
+    pub const COUNTRY_NAME: Tag = Tag::new(2, 101);
+    pub const HEADLINE: Tag = Tag::new(2, 105);
+    pub const CREDIT: Tag = Tag::new(2, 110);
+    pub const SOURCE: Tag = Tag::new(2, 115);
+    pub const COPYRIGHT_NOTICE: Tag = Tag::new(2, 116);
+    pub const CAPTION: Tag = Tag::new(2, 120);
+    pub const CAPTION_WRITER: Tag = Tag::new(2, 122);
+
+    /// Dataset `dataset` of record `record`.
+    pub const fn new(record: u8, dataset: u8) -> Self {
+        Tag { record, dataset }
+    }
+
+    /// Record number, e.g. 2 for the application record.
+    pub fn record(&self) -> u8 {
+        self.record
+    }
+
+    /// Dataset number in the record.
+    pub fn dataset(&self) -> u8 {
+        self.dataset
+    }
+
+    /// Whether the values are text, i.e. the datasets of the application
+    /// record but the record version, the rasterized caption and the
+    /// preview.
+    fn is_text(&self) -> bool {
+        self.record == 2 && !matches!(self.dataset, 0 | 125 | 200..=202)
+    }
+}
+
+/// Error returned by [Iptc::parse].
+#[derive(ThisError, Debug, Clone, Eq, PartialEq)]
+#[error("invalid IPTC profile: {reason}")]
+pub struct ParseIptcError {
+    reason: &'static str,
+}
+
+impl ParseIptcError {
+    /// Why the profile was rejected.
+    pub fn get_reason(&self) -> &'static str {
+        self.reason
+    }
+}
+
+/// IPTC-IIM datasets, in file order. Datasets such as [Tag::KEYWORDS] may be
+/// repeated.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Iptc {
+    datasets: Vec<(Tag, Vec<u8>)>,
+}
+
+impl Iptc {
+    /// Create a profile without any dataset.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Parse an `IPTC` or `8BIM` profile, either Photoshop image resources,
+    /// optionally prefixed by `Photoshop 3.0\0`, or bare IPTC-IIM datasets.
+    ///
+    /// Image resources without IPTC datasets give an empty [Iptc].
+    pub fn parse(data: &[u8]) -> Result<Self, ParseIptcError> {
+        let data = data.strip_prefix(PHOTOSHOP_PREFIX).unwrap_or(data);
+        if data.starts_with(RESOURCE_SIGNATURE) {
+            let resources = parse_resources(data)?;
+            match resources
+                .into_iter()
+                .find(|(id, ..)| *id == IPTC_RESOURCE_ID)
+            {
+                Some((_, _, data)) => parse_datasets(data),
+                None => Ok(Iptc::new()),
+            }
+        } else {
+            parse_datasets(data)
+        }
+    }
+
+    /// Serialize the datasets as a single IPTC image resource, as expected
+    /// by [MagickWand::set_iptc].
+    ///
+    /// The coded character set is declared as UTF-8, so the text values which
+    /// aren't UTF-8, typically from legacy Latin-1 profiles, are transcoded
+    /// as read by [Iptc::get_str]. The record version is added if missing.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_resource(&mut bytes, IPTC_RESOURCE_ID, b"", &self.datasets_bytes());
+        bytes
+    }
+
+    /// Bare IPTC-IIM datasets, records in ascending order.
+    fn datasets_bytes(&self) -> Vec<u8> {
+        let mut datasets: Vec<(Tag, Cow<'_, [u8]>)> = self
+            .datasets
+            .iter()
+            .filter(|(tag, _)| *tag != Tag::CODED_CHARACTER_SET)
+            .map(|(tag, data)| match std::str::from_utf8(data) {
+                Err(_) if tag.is_text() => (*tag, Cow::Owned(decode(data).into_bytes())),
+                _ => (*tag, Cow::Borrowed(&data[..])),
+            })
+            .collect();
+        datasets.push((Tag::CODED_CHARACTER_SET, UTF8_CHARACTER_SET.into()));
+        if self.get(Tag::RECORD_VERSION).is_none() {
+            datasets.push((Tag::RECORD_VERSION, Cow::Borrowed(&[0, 4])));
+        }
+        // Stable, so that repeated datasets keep their order.
+        datasets.sort_by_key(|(tag, _)| (tag.record, *tag != Tag::RECORD_VERSION));
+
+        let mut bytes = Vec::new();
+        for (tag, data) in datasets {
+            bytes.extend_from_slice(&[TAG_MARKER, tag.record, tag.dataset]);
+            if data.len() < 0x8000 {
+                bytes.extend_from_slice(&(data.len() as u16).to_be_bytes());
+            } else {
+                // Extended dataset, the length is stored on 4 bytes.
+                bytes.extend_from_slice(&0x8004_u16.to_be_bytes());
+                bytes.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            }
+            bytes.extend_from_slice(&data);
+        }
+        bytes
+    }
+
+    /// First value of `tag`.
+    pub fn get(&self, tag: Tag) -> Option<&[u8]> {
+        self.get_all(tag).next()
+    }
+
+    /// All values of `tag`, in file order.
+    pub fn get_all(&self, tag: Tag) -> impl Iterator<Item = &[u8]> {
+        self.datasets
+            .iter()
+            .filter(move |(t, _)| *t == tag)
+            .map(|(_, data)| &data[..])
+    }
+
+    /// First value of `tag` as text.
+    ///
+    /// Values which aren't UTF-8 are decoded as Latin-1, the most common
+    /// encoding of legacy IPTC.
+    pub fn get_str(&self, tag: Tag) -> Option<String> {
+        self.get(tag).map(decode)
+    }
+
+    /// All values of `tag` as text, see [Iptc::get_str].
+    pub fn get_all_str(&self, tag: Tag) -> Vec<String> {
+        self.get_all(tag).map(decode).collect()
+    }
+
+    /// Replace the values of `tag` by `value`.
+    pub fn set(&mut self, tag: Tag, value: impl Into<Vec<u8>>) {
+        self.set_all(tag, [value]);
+    }
+
+    /// Replace the values of `tag` by `values`, keeping the position of the
+    /// first one.
+    pub fn set_all<V: Into<Vec<u8>>>(&mut self, tag: Tag, values: impl IntoIterator<Item = V>) {
+        let position = self
+            .datasets
+            .iter()
+            .position(|(t, _)| *t == tag)
+            .unwrap_or(self.datasets.len());
+        self.remove(tag);
+        self.datasets.splice(
+            position..position,
+            values.into_iter().map(|value| (tag, value.into())),
+        );
+    }
+
+    /// Append a value to `tag`, for repeatable datasets.
+    pub fn add(&mut self, tag: Tag, value: impl Into<Vec<u8>>) {
+        self.datasets.push((tag, value.into()));
+    }
+
+    /// Remove all values of `tag`.
+    pub fn remove(&mut self, tag: Tag) {
+        self.datasets.retain(|(t, _)| *t != tag);
+    }
+
+    /// Whether there is no dataset.
+    pub fn is_empty(&self) -> bool {
+        self.datasets.is_empty()
+    }
+
+    /// All datasets, in file order.
+    pub fn iter(&self) -> impl Iterator<Item = (Tag, &[u8])> {
+        self.datasets.iter().map(|(tag, data)| (*tag, &data[..]))
+    }
+
+    /// Caption, or abstract.
+    pub fn caption(&self) -> Option<String> {
+        self.get_str(Tag::CAPTION)
+    }
+
+    /// Replace the caption.
+    pub fn set_caption(&mut self, caption: &str) {
+        self.set(Tag::CAPTION, caption);
+    }
+
+    /// Headline, a short synopsis of the caption.
+    pub fn headline(&self) -> Option<String> {
+        self.get_str(Tag::HEADLINE)
+    }
+
+    /// Replace the headline.
+    pub fn set_headline(&mut self, headline: &str) {
+        self.set(Tag::HEADLINE, headline);
+    }
+
+    /// Title, or object name.
+    pub fn object_name(&self) -> Option<String> {
+        self.get_str(Tag::OBJECT_NAME)
+    }
+
+    /// Replace the title.
+    pub fn set_object_name(&mut self, object_name: &str) {
+        self.set(Tag::OBJECT_NAME, object_name);
+    }
+
+    /// Keywords, in file order.
+    pub fn keywords(&self) -> Vec<String> {
+        self.get_all_str(Tag::KEYWORDS)
+    }
+
+    /// Replace the keywords, one dataset each.
+    pub fn set_keywords<'k>(&mut self, keywords: impl IntoIterator<Item = &'k str>) {
+        self.set_all(Tag::KEYWORDS, keywords);
+    }
+
+    /// Creators, or by-lines.
+    pub fn by_lines(&self) -> Vec<String> {
+        self.get_all_str(Tag::BY_LINE)
+    }
+
+    /// Replace the creators, one dataset each.
+    pub fn set_by_lines<'b>(&mut self, by_lines: impl IntoIterator<Item = &'b str>) {
+        self.set_all(Tag::BY_LINE, by_lines);
+    }
+
+    /// Copyright notice.
+    pub fn copyright_notice(&self) -> Option<String> {
+        self.get_str(Tag::COPYRIGHT_NOTICE)
+    }
+
+    /// Replace the copyright notice.
+    pub fn set_copyright_notice(&mut self, copyright_notice: &str) {
+        self.set(Tag::COPYRIGHT_NOTICE, copyright_notice);
+    }
+}
+
+fn decode(data: &[u8]) -> String {
+    match std::str::from_utf8(data) {
+        Ok(text) => text.to_owned(),
+        Err(_) => data.iter().map(|&b| char::from(b)).collect(),
+    }
+}
+
+/// Photoshop image resource, as `(id, name, data)`.
+type Resource<'d> = (u16, &'d [u8], &'d [u8]);
+
+fn parse_resources(mut data: &[u8]) -> Result<Vec<Resource<'_>>, ParseIptcError> {
+    let error = |reason| ParseIptcError { reason };
+
+    let mut resources = Vec::new();
+    while !data.is_empty() {
+        let rest = data
+            .strip_prefix(RESOURCE_SIGNATURE)
+            .ok_or_else(|| error("missing image resource signature"))?;
+        let truncated = || error("truncated image resource");
+
+        let id = u16::from_be_bytes(rest.get(..2).ok_or_else(truncated)?.try_into().unwrap());
+        let name_len = usize::from(*rest.get(2).ok_or_else(truncated)?);
+        let name = rest.get(3..3 + name_len).ok_or_else(truncated)?;
+        // The Pascal string, with its length byte, is padded to an even size.
+        let rest = &rest[(2 + (1 + name_len).next_multiple_of(2)).min(rest.len())..];
+        let size = rest.get(..4).ok_or_else(truncated)?;
+        let size = u32::from_be_bytes(size.try_into().unwrap()) as usize;
+        let resource = rest.get(4..4 + size).ok_or_else(truncated)?;
+
+        resources.push((id, name, resource));
+        data = rest.get(4 + size.next_multiple_of(2)..).unwrap_or_default();
+    }
+    Ok(resources)
+}
+
+/// Append a Photoshop image resource to `buf`.
+fn write_resource(buf: &mut Vec<u8>, id: u16, name: &[u8], data: &[u8]) {
+    buf.extend_from_slice(RESOURCE_SIGNATURE);
+    buf.extend_from_slice(&id.to_be_bytes());
+    buf.push(name.len() as u8);
+    buf.extend_from_slice(name);
+    if name.len() % 2 == 0 {
+        buf.push(0);
+    }
+    buf.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    buf.extend_from_slice(data);
+    if data.len() % 2 == 1 {
+        buf.push(0);
+    }
+}
+
+fn parse_datasets(mut data: &[u8]) -> Result<Iptc, ParseIptcError> {
+    let error = |reason| ParseIptcError { reason };
+
+    let mut iptc = Iptc::new();
+    // Resources are padded to an even size.
+    while data.first().is_some_and(|&b| b != 0) {
+        let [TAG_MARKER, record, dataset, l0, l1, rest @ ..] = data else {
+            return Err(error("invalid dataset header"));
+        };
+        let len = u16::from_be_bytes([*l0, *l1]);
+        let (len, rest) = if len & 0x8000 == 0 {
+            (usize::from(len), rest)
+        } else {
+            let size = usize::from(len & 0x7fff);
+            let len = rest
+                .get(..size)
+                .filter(|len| len.len() <= 8)
+                .ok_or_else(|| error("invalid extended dataset length"))?;
+            let len = len.iter().fold(0_u64, |acc, &b| (acc << 8) | u64::from(b));
+            (
+                usize::try_from(len).map_err(|_| error("invalid extended dataset length"))?,
+                &rest[size..],
+            )
+        };
+        let value = rest.get(..len).ok_or_else(|| error("truncated dataset"))?;
+        iptc.datasets
+            .push((Tag::new(*record, *dataset), value.to_vec()));
+        data = &rest[len..];
+    }
+    Ok(iptc)
+}
+
+/// Profile names GraphicsMagick uses for image resources, by preference.
+const PROFILE_NAMES: [&str; 2] = ["IPTC", "8BIM"];
+
+impl MagickWand<'_> {
+    /// Parse the `IPTC` profile of the image, or else its `8BIM` profile,
+    /// see [Iptc].
+    ///
+    /// # Return
+    ///
+    /// `None` if the image has neither profile, an error if the wand has no
+    /// image.
+    pub fn get_iptc(&mut self) -> crate::Result<Option<Iptc>> {
+        self.check_has_images()?;
+        for name in PROFILE_NAMES {
+            if let Some(profile) = self.get_image_profile_bytes(name) {
+                return Ok(Some(Iptc::parse(&profile)?));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Replace the IPTC datasets of the image by `iptc`.
+    ///
+    /// The other image resources of the profile, such as the Photoshop
+    /// clipping paths, are kept.
+    pub fn set_iptc(&mut self, iptc: &Iptc) -> crate::Result<&mut Self> {
+        for name in PROFILE_NAMES {
+            if let Some(profile) = self.get_image_profile_bytes(name) {
+                let profile = profile
+                    .strip_prefix(PHOTOSHOP_PREFIX)
+                    .unwrap_or(&profile[..]);
+                if !profile.starts_with(RESOURCE_SIGNATURE) {
+                    break;
+                }
+
+                let mut buf = Vec::new();
+                let datasets = iptc.datasets_bytes();
+                let mut written = false;
+                for (id, resource_name, data) in parse_resources(profile)? {
+                    if id != IPTC_RESOURCE_ID {
+                        write_resource(&mut buf, id, resource_name, data);
+                    } else if !written {
+                        write_resource(&mut buf, id, resource_name, &datasets);
+                        written = true;
+                    }
+                }
+                if !written {
+                    write_resource(&mut buf, IPTC_RESOURCE_ID, b"", &datasets);
+                }
+                return self.set_image_profile_bytes(name, &buf);
+            }
+        }
+        self.set_image_profile_bytes(PROFILE_NAMES[0], &iptc.to_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::new_logo_magick_wand;
+
+    fn new_iptc() -> Iptc {
+        let mut iptc = Iptc::new();
+        iptc.set_object_name("Logo");
+        iptc.set_caption("Le logo, écrit en UTF-8");
+        iptc.set_keywords(["logo", "rust", "magick"]);
+        iptc.set_by_lines(["jmjoy"]);
+        iptc.set_copyright_notice("MIT");
+        iptc
+    }
+
+    #[test]
+    fn test_iptc_round_trip() {
+        let iptc = new_iptc();
+        let parsed = Iptc::parse(&iptc.to_bytes()).unwrap();
+        assert_eq!(parsed.object_name().as_deref(), Some("Logo"));
+        assert_eq!(parsed.caption().as_deref(), Some("Le logo, écrit en UTF-8"));
+        assert_eq!(parsed.keywords(), ["logo", "rust", "magick"]);
+        assert_eq!(parsed.by_lines(), ["jmjoy"]);
+        assert_eq!(parsed.copyright_notice().as_deref(), Some("MIT"));
+        assert_eq!(
+            parsed.get(Tag::CODED_CHARACTER_SET),
+            Some(UTF8_CHARACTER_SET)
+        );
+        assert_eq!(parsed.get(Tag::RECORD_VERSION), Some(&[0, 4][..]));
+
+        // Bare datasets and the Photoshop prefix are accepted too.
+        let mut prefixed = PHOTOSHOP_PREFIX.to_vec();
+        prefixed.extend_from_slice(&iptc.to_bytes());
+        assert_eq!(Iptc::parse(&prefixed).unwrap(), parsed);
+        assert_eq!(Iptc::parse(&iptc.datasets_bytes()).unwrap(), parsed);
+    }
+
+    #[test]
+    fn test_iptc_edit() {
+        let mut iptc = new_iptc();
+        iptc.set_keywords(["edited"]);
+        iptc.add(Tag::KEYWORDS, "added");
+        assert_eq!(iptc.keywords(), ["edited", "added"]);
+        iptc.remove(Tag::CAPTION);
+        assert_eq!(iptc.caption(), None);
+
+        let long = "x".repeat(0x9000);
+        iptc.set(Tag::SPECIAL_INSTRUCTIONS, long.as_str());
+        let parsed = Iptc::parse(&iptc.to_bytes()).unwrap();
+        assert_eq!(parsed.get_str(Tag::SPECIAL_INSTRUCTIONS), Some(long));
+        assert_eq!(parsed.keywords(), ["edited", "added"]);
+    }
+
+    #[test]
+    fn test_iptc_latin1() {
+        let iptc = Iptc::parse(b"\x1c\x02\x78\x00\x04caf\xe9\x1c\x02\xca\x00\x02\xff\xd8").unwrap();
+        assert_eq!(iptc.caption().as_deref(), Some("café"));
+
+        // Written back as UTF-8, as declared, but for the binary preview.
+        let parsed = Iptc::parse(&iptc.to_bytes()).unwrap();
+        assert_eq!(parsed.get(Tag::CAPTION), Some("café".as_bytes()));
+        assert_eq!(parsed.get(Tag::new(2, 202)), Some(&[0xff, 0xd8][..]));
+        assert_eq!(
+            parsed.get(Tag::CODED_CHARACTER_SET),
+            Some(UTF8_CHARACTER_SET)
+        );
+    }
+
+    #[test]
+    fn test_iptc_parse_invalid() {
+        assert!(Iptc::parse(b"\x1c\x02").is_err());
+        assert_eq!(
+            Iptc::parse(b"\x1c\x02\x78\x00\x05caf\xe9")
+                .unwrap_err()
+                .get_reason(),
+            "truncated dataset"
+        );
+        assert!(Iptc::parse(b"8BIM\x04\x04\x00\x00\x00\x00\x00\xff").is_err());
+        assert_eq!(Iptc::parse(b"").unwrap(), Iptc::new());
+    }
+
+    #[test]
+    fn test_iptc_keep_other_resources() {
+        let mut mw = new_logo_magick_wand();
+        let mut profile = Vec::new();
+        write_resource(&mut profile, 0x03ed, b"", &[0; 16]);
+        write_resource(
+            &mut profile,
+            IPTC_RESOURCE_ID,
+            b"",
+            b"\x1c\x02\x05\x00\x03old",
+        );
+        mw.set_image_profile_bytes("IPTC", &profile).unwrap();
+
+        mw.set_iptc(&new_iptc()).unwrap();
+        let profile = mw.get_image_profile_bytes("IPTC").unwrap();
+        let resources = parse_resources(&profile).unwrap();
+        assert_eq!(resources.len(), 2);
+        assert_eq!(resources[0], (0x03ed, &b""[..], &[0; 16][..]));
+        assert_eq!(
+            mw.get_iptc().unwrap().unwrap().object_name().as_deref(),
+            Some("Logo")
+        );
+    }
+
+    #[test]
+    fn test_iptc_jpeg_round_trip() {
+        crate::initialize();
+        assert!(MagickWand::new().get_iptc().is_err());
+
+        let mut mw = new_logo_magick_wand();
+        assert_eq!(mw.get_iptc().unwrap(), None);
+
+        let iptc = new_iptc();
+        mw.set_iptc(&iptc).unwrap();
+        mw.set_image_format("JPEG").unwrap();
+        let blob = mw.write_image_blob().unwrap();
+
+        let mut mw = MagickWand::new();
+        mw.read_image_blob(&blob).unwrap();
+        let parsed = mw.get_iptc().unwrap().unwrap();
+        assert_eq!(parsed, Iptc::parse(&iptc.to_bytes()).unwrap());
+        assert_eq!(parsed.keywords(), ["logo", "rust", "magick"]);
+        assert_eq!(parsed.caption().as_deref(), Some("Le logo, écrit en UTF-8"));
+    }
+}
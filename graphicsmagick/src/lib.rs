@@ -4,15 +4,35 @@
 #![allow(clippy::too_many_arguments)]
 #![doc = include_str!("../README.md")]
 
+pub mod animation;
+pub mod color;
+pub mod compare;
 pub mod error;
+pub mod exif;
+pub mod format;
+pub mod geometry;
+#[cfg(any(feature = "image", feature = "ndarray"))]
+mod interop;
+pub mod iptc;
+pub mod perceptual_hash;
+pub mod pipeline;
+pub mod probe;
+pub mod resource;
+pub mod smart_crop;
+#[cfg(feature = "testing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub mod testing;
 #[cfg(test)]
 pub(crate) mod tests;
+pub mod thumbnail;
 pub mod types;
 pub(crate) mod utils;
 pub mod wand;
+pub mod xmp;
 
 pub use crate::{
     error::{Error, Result},
+    resource::initialize_with_limits,
     utils::{MagickBoxSlice, MagickCString, MaxRGB, has_initialized, initialize, max_rgb},
 };
 
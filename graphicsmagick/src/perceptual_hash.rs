@@ -0,0 +1,440 @@
+//! Perceptual hashes, to find near-duplicate images.
+//!
+//! Unlike [MagickWand::get_image_signature], which only matches identical
+//! pixels, a [PerceptualHash] of an image stays close, in Hamming distance,
+//! to the hash of a resized or recompressed copy.
+//!
+//! ```no_run
+//! use graphicsmagick::{
+//!     initialize,
+//!     perceptual_hash::{HashAlgorithm, HashIndex},
+//!     wand::MagickWand,
+//! };
+//!
+//! initialize();
+//!
+//! let mut index = HashIndex::new();
+//! for path in ["a.jpg", "b.jpg", "c.jpg"] {
+//!     let mut mw = MagickWand::new();
+//!     mw.read_image(path).unwrap();
+//!     let hash = mw.perceptual_hash(HashAlgorithm::Perceptual).unwrap();
+//!     for (distance, duplicate) in index.find(hash, 8) {
+//!         println!("{} looks like {} ({} bits differ)", path, duplicate, distance);
+//!     }
+//!     index.insert(hash, path);
+//! }
+//! ```
+
+use crate::wand::MagickWand;
+use std::{f64::consts::PI, fmt, os::raw::c_ulong};
+
+/// Algorithm of a [PerceptualHash].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum HashAlgorithm {
+    /// aHash: whether each pixel of an 8x8 grayscale thumbnail is brighter
+    /// than the mean. Fast, but sensitive to contrast changes.
+    Average,
+    /// dHash: whether each pixel of a 9x8 grayscale thumbnail is brighter
+    /// than its right neighbour, i.e. the horizontal gradients.
+    Difference,
+    /// pHash: whether each of the 8x8 lowest frequencies of the DCT of a
+    /// 32x32 grayscale thumbnail is above their median. The most robust.
+    Perceptual,
+}
+
+impl HashAlgorithm {
+    /// Size of the thumbnail the hash is computed from.
+    fn thumbnail_size(self) -> (usize, usize) {
+        match self {
+            HashAlgorithm::Average => (8, 8),
+            HashAlgorithm::Difference => (9, 8),
+            HashAlgorithm::Perceptual => (32, 32),
+        }
+    }
+
+    /// Compute the hash of a row-major grayscale thumbnail.
+    fn hash(self, pixels: &[f64]) -> u64 {
+        let (width, _) = self.thumbnail_size();
+        match self {
+            HashAlgorithm::Average => {
+                let mean = pixels.iter().sum::<f64>() / pixels.len() as f64;
+                bits(pixels.iter().map(|&pixel| pixel > mean))
+            }
+            HashAlgorithm::Difference => bits(
+                pixels
+                    .chunks_exact(width)
+                    .flat_map(|row| row.windows(2).map(|pair| pair[0] > pair[1])),
+            ),
+            HashAlgorithm::Perceptual => {
+                let frequencies = low_frequencies(pixels, width);
+                let mut sorted = frequencies;
+                sorted.sort_by(f64::total_cmp);
+                let median = (sorted[31] + sorted[32]) / 2.;
+                bits(frequencies.iter().map(|&frequency| frequency > median))
+            }
+        }
+    }
+}
+
+/// Pack 64 booleans, the first one being the most significant bit.
+fn bits(bits: impl Iterator<Item = bool>) -> u64 {
+    bits.fold(0, |hash, bit| (hash << 1) | u64::from(bit))
+}
+
+/// The 8x8 lowest frequencies of the 2D DCT-II of a `size`x`size` image.
+fn low_frequencies(pixels: &[f64], size: usize) -> [f64; 64] {
+    let cosines: Vec<f64> = (0..8)
+        .flat_map(|u| {
+            (0..size).map(move |x| ((2 * x + 1) as f64 * u as f64 * PI / (2 * size) as f64).cos())
+        })
+        .collect();
+    let cosine = |u: usize, x: usize| cosines[u * size + x];
+
+    // Rows first, then columns, as the DCT is separable.
+    let mut rows = vec![0.; size * 8];
+    for y in 0..size {
+        for u in 0..8 {
+            rows[y * 8 + u] = (0..size).map(|x| pixels[y * size + x] * cosine(u, x)).sum();
+        }
+    }
+    let mut frequencies = [0.; 64];
+    for v in 0..8 {
+        for u in 0..8 {
+            frequencies[v * 8 + u] = (0..size).map(|y| rows[y * 8 + u] * cosine(v, y)).sum();
+        }
+    }
+    frequencies
+}
+
+/// 64 bits perceptual hash of an image, see [MagickWand::perceptual_hash].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PerceptualHash {
+    algorithm: HashAlgorithm,
+    bits: u64,
+}
+
+impl PerceptualHash {
+    /// Rebuild a hash from its [PerceptualHash::bits], e.g. when loaded from
+    /// a database.
+    pub fn new(algorithm: HashAlgorithm, bits: u64) -> Self {
+        PerceptualHash { algorithm, bits }
+    }
+
+    pub fn algorithm(&self) -> HashAlgorithm {
+        self.algorithm
+    }
+
+    pub fn bits(&self) -> u64 {
+        self.bits
+    }
+
+    /// Hamming distance, i.e. number of different bits, between `0` for
+    /// similar images and `64`.
+    ///
+    /// # Panic
+    ///
+    /// Panic if the hashes don't come from the same algorithm.
+    pub fn distance(&self, other: &PerceptualHash) -> u32 {
+        assert_eq!(
+            self.algorithm, other.algorithm,
+            "hashes of different algorithms can't be compared"
+        );
+        (self.bits ^ other.bits).count_ones()
+    }
+}
+
+/// Formats the bits as 16 hexadecimal digits.
+impl fmt::Display for PerceptualHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.bits)
+    }
+}
+
+/// Index of [PerceptualHash] to find the ones within a distance of a hash,
+/// without comparing it to every hash of the index.
+///
+/// It's a BK-tree: each child of a node is keyed by its distance to the node,
+/// so that the triangle inequality prunes the subtrees which can't match.
+/// Hashes of different algorithms can't be compared, so they are kept in
+/// separate trees, one per algorithm.
+#[derive(Debug, Clone)]
+pub struct HashIndex<T> {
+    nodes: Vec<Node<T>>,
+    /// Root node of the tree of each algorithm.
+    roots: Vec<(HashAlgorithm, usize)>,
+}
+
+#[derive(Debug, Clone)]
+struct Node<T> {
+    hash: PerceptualHash,
+    value: T,
+    /// `(distance to this node, index of the child)`.
+    children: Vec<(u32, usize)>,
+}
+
+impl<T> Default for HashIndex<T> {
+    fn default() -> Self {
+        HashIndex {
+            nodes: Vec::new(),
+            roots: Vec::new(),
+        }
+    }
+}
+
+impl<T> HashIndex<T> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    fn root(&self, algorithm: HashAlgorithm) -> Option<usize> {
+        self.roots
+            .iter()
+            .find(|(root_algorithm, _)| *root_algorithm == algorithm)
+            .map(|(_, root)| *root)
+    }
+
+    /// Add `value`, e.g. an image id, with its `hash`.
+    pub fn insert(&mut self, hash: PerceptualHash, value: T) {
+        let new = self.nodes.len();
+        let mut current = self.root(hash.algorithm).unwrap_or_else(|| {
+            self.roots.push((hash.algorithm, new));
+            new
+        });
+        while current < new {
+            let distance = self.nodes[current].hash.distance(&hash);
+            match self.nodes[current]
+                .children
+                .iter()
+                .find(|(d, _)| *d == distance)
+            {
+                Some(&(_, child)) => current = child,
+                None => {
+                    self.nodes[current].children.push((distance, new));
+                    break;
+                }
+            }
+        }
+        self.nodes.push(Node {
+            hash,
+            value,
+            children: Vec::new(),
+        });
+    }
+
+    /// Values whose hash is within `max_distance` of `hash`, with their
+    /// distance, closest first. Only the hashes of the same algorithm as
+    /// `hash` are searched.
+    pub fn find(&self, hash: PerceptualHash, max_distance: u32) -> Vec<(u32, &T)> {
+        let mut found = Vec::new();
+        let mut pending: Vec<usize> = self.root(hash.algorithm).into_iter().collect();
+        while let Some(current) = pending.pop() {
+            let node = &self.nodes[current];
+            let distance = node.hash.distance(&hash);
+            if distance <= max_distance {
+                found.push((distance, &node.value));
+            }
+            let range =
+                distance.saturating_sub(max_distance)..=distance.saturating_add(max_distance);
+            pending.extend(
+                node.children
+                    .iter()
+                    .filter(|(d, _)| range.contains(d))
+                    .map(|(_, child)| *child),
+            );
+        }
+        found.sort_by_key(|(distance, _)| *distance);
+        found
+    }
+
+    /// All values with their hash, in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (PerceptualHash, &T)> {
+        self.nodes.iter().map(|node| (node.hash, &node.value))
+    }
+}
+
+impl MagickWand<'_> {
+    /// Compute the perceptual hash of the current image, from a grayscale
+    /// thumbnail made by [MagickWand::scale_image], which leaves the image
+    /// untouched.
+    ///
+    /// Compare hashes with [PerceptualHash::distance] or a [HashIndex].
+    pub fn perceptual_hash(&mut self, algorithm: HashAlgorithm) -> crate::Result<PerceptualHash> {
+        let Some(mut thumbnail) = self.get_image() else {
+            return Err(unsafe { self.get_error() });
+        };
+        let (width, height) = algorithm.thumbnail_size();
+        let (width, height) = (width as c_ulong, height as c_ulong);
+        thumbnail.scale_image(width, height)?;
+        let pixels = thumbnail.get_image_pixels::<f64>(0, 0, width, height, "I")?;
+        Ok(PerceptualHash::new(algorithm, algorithm.hash(&pixels)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{initialize, tests::new_logo_magick_wand};
+
+    const ALGORITHMS: [HashAlgorithm; 3] = [
+        HashAlgorithm::Average,
+        HashAlgorithm::Difference,
+        HashAlgorithm::Perceptual,
+    ];
+
+    /// Deterministic pseudo-random hashes.
+    fn random_hashes(count: usize) -> Vec<PerceptualHash> {
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        (0..count)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                PerceptualHash::new(HashAlgorithm::Perceptual, state)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_perceptual_hash_distance() {
+        let a = PerceptualHash::new(HashAlgorithm::Difference, 0b1011);
+        let b = PerceptualHash::new(HashAlgorithm::Difference, 0b0110);
+        assert_eq!(a.distance(&b), 3);
+        assert_eq!(a.distance(&a), 0);
+        assert_eq!(a.to_string(), "000000000000000b");
+    }
+
+    #[test]
+    #[should_panic(expected = "hashes of different algorithms can't be compared")]
+    fn test_perceptual_hash_distance_algorithms() {
+        let a = PerceptualHash::new(HashAlgorithm::Average, 0);
+        let b = PerceptualHash::new(HashAlgorithm::Difference, 0);
+        a.distance(&b);
+    }
+
+    #[test]
+    fn test_hash_algorithm_hash() {
+        let gradient: Vec<f64> = (0..8).flat_map(|_| (0..9).map(f64::from)).collect();
+        assert_eq!(HashAlgorithm::Difference.hash(&gradient), 0);
+        let gradient: Vec<f64> = gradient.iter().map(|pixel| -pixel).collect();
+        assert_eq!(HashAlgorithm::Difference.hash(&gradient), u64::MAX);
+
+        let halves: Vec<f64> = (0..64).map(|i| if i < 32 { 1. } else { 0. }).collect();
+        assert_eq!(HashAlgorithm::Average.hash(&halves), 0xffff_ffff_0000_0000);
+
+        let pattern: Vec<f64> = (0..32 * 32)
+            .map(|i| ((i % 32 * 7 + i / 32 * 13) % 17) as f64)
+            .collect();
+        let hash = HashAlgorithm::Perceptual.hash(&pattern);
+        let contrasted: Vec<f64> = pattern.iter().map(|pixel| pixel * 2.).collect();
+        assert_eq!(HashAlgorithm::Perceptual.hash(&contrasted), hash);
+        let negated: Vec<f64> = pattern.iter().map(|pixel| -pixel).collect();
+        let negated = HashAlgorithm::Perceptual.hash(&negated);
+        assert!((hash ^ negated).count_ones() > 60);
+    }
+
+    #[test]
+    fn test_hash_index_find() {
+        let hashes = random_hashes(500);
+        let mut index = HashIndex::new();
+        for (i, hash) in hashes.iter().enumerate() {
+            index.insert(*hash, i);
+        }
+        assert_eq!(index.len(), 500);
+
+        for query in random_hashes(520).into_iter().skip(480) {
+            for max_distance in [0, 10, 24, 30] {
+                let found: Vec<_> = index
+                    .find(query, max_distance)
+                    .into_iter()
+                    .map(|(distance, i)| (distance, *i))
+                    .collect();
+                assert!(found.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+
+                // Same as a linear scan.
+                let mut found = found;
+                found.sort();
+                let mut expected: Vec<_> = hashes
+                    .iter()
+                    .enumerate()
+                    .map(|(i, hash)| (hash.distance(&query), i))
+                    .filter(|(distance, _)| *distance <= max_distance)
+                    .collect();
+                expected.sort();
+                assert_eq!(found, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hash_index_algorithms() {
+        let mut index = HashIndex::new();
+        index.insert(PerceptualHash::new(HashAlgorithm::Perceptual, 0), "p");
+        index.insert(PerceptualHash::new(HashAlgorithm::Average, 0), "a");
+        index.insert(PerceptualHash::new(HashAlgorithm::Average, 1), "a1");
+        index.insert(PerceptualHash::new(HashAlgorithm::Perceptual, 3), "p3");
+        assert_eq!(index.len(), 4);
+
+        let found = index.find(PerceptualHash::new(HashAlgorithm::Average, 0), u32::MAX);
+        assert_eq!(found, [(0, &"a"), (1, &"a1")]);
+        let found = index.find(PerceptualHash::new(HashAlgorithm::Perceptual, 1), 1);
+        assert_eq!(found, [(1, &"p"), (1, &"p3")]);
+        assert!(
+            index
+                .find(PerceptualHash::new(HashAlgorithm::Difference, 0), 64)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_magick_wand_perceptual_hash() {
+        let mut mw = new_logo_magick_wand();
+        let mut copy = mw.clone();
+        copy.resize_image(300, 300, crate::types::FilterTypes::LanczosFilter, 1.)
+            .unwrap();
+        copy.set_image_format("JPEG").unwrap();
+        copy.set_compression_quality(50).unwrap();
+        let blob = copy.write_image_blob().unwrap();
+        let mut copy = MagickWand::new();
+        copy.read_image_blob(&blob).unwrap();
+
+        let mut unrelated = MagickWand::new();
+        unrelated.set_size(1024, 1024).unwrap();
+        unrelated.read_image("gradient:white-black").unwrap();
+
+        for algorithm in ALGORITHMS {
+            let hash = mw.perceptual_hash(algorithm).unwrap();
+            assert_eq!(hash.algorithm(), algorithm);
+            assert!(hash.distance(&copy.perceptual_hash(algorithm).unwrap()) <= 6);
+        }
+        // The image itself is untouched.
+        assert_eq!(mw.get_image_width(), 1024);
+
+        let mut index = HashIndex::new();
+        let hash = mw.perceptual_hash(HashAlgorithm::Perceptual).unwrap();
+        index.insert(hash, "logo");
+        let copy_hash = copy.perceptual_hash(HashAlgorithm::Perceptual).unwrap();
+        assert_eq!(index.find(copy_hash, 10).len(), 1);
+        let unrelated_hash = unrelated
+            .perceptual_hash(HashAlgorithm::Perceptual)
+            .unwrap();
+        assert!(index.find(unrelated_hash, 10).is_empty());
+    }
+
+    #[test]
+    fn test_magick_wand_perceptual_hash_empty() {
+        initialize();
+        let mut mw = MagickWand::new();
+        assert!(mw.perceptual_hash(HashAlgorithm::Average).is_err());
+    }
+}
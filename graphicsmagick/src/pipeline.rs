@@ -0,0 +1,428 @@
+//! Declarative image-processing pipeline built on top of [MagickWand].
+//!
+//! A [Pipeline] is a plain list of [Operation]s. It can be built in code,
+//! or deserialized from a config file when the `serde` feature is enabled.
+//! It is validated up front and can then be applied to any number of wands.
+//!
+//! ```no_run
+//! use graphicsmagick::{initialize, pipeline::Pipeline, wand::MagickWand};
+//!
+//! initialize();
+//!
+//! let pipeline = Pipeline::new()
+//!     .resize(320, 240)
+//!     .sharpen(0., 1.)
+//!     .strip()
+//!     .quality(85)
+//!     .format("JPEG");
+//! pipeline.validate().unwrap();
+//!
+//! let mut mw = MagickWand::new();
+//! mw.read_image("input.png").unwrap();
+//! pipeline.apply(&mut mw).unwrap();
+//! mw.write_image("output.jpg").unwrap();
+//! ```
+
+use crate::{color::Color, types::FilterTypes, wand::MagickWand};
+use std::os::raw::{c_double, c_long, c_ulong};
+use thiserror::Error as ThisError;
+
+/// Single step of a [Pipeline].
+///
+/// Every variant maps to one [MagickWand] method.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "op", rename_all = "snake_case"))]
+pub enum Operation {
+    /// See [MagickWand::resize_image].
+    Resize {
+        width: c_ulong,
+        height: c_ulong,
+        filter: FilterTypes,
+        blur: c_double,
+    },
+    /// See [MagickWand::crop_image].
+    Crop {
+        width: c_ulong,
+        height: c_ulong,
+        x: c_long,
+        y: c_long,
+    },
+    /// See [MagickWand::rotate_image].
+    ///
+    /// `background` is any color accepted by [Color]'s `FromStr`, e.g.
+    /// `"white"` or `"#ff000080"`.
+    Rotate {
+        degrees: c_double,
+        background: String,
+    },
+    /// See [MagickWand::sharpen_image].
+    Sharpen { radius: c_double, sigma: c_double },
+    /// See [MagickWand::strip_image].
+    Strip,
+    /// See [MagickWand::set_compression_quality], in range `0..=100`.
+    Quality { quality: c_ulong },
+    /// See [MagickWand::set_image_format].
+    Format { format: String },
+}
+
+impl Operation {
+    /// Name of the operation, used in validation errors.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Operation::Resize { .. } => "resize",
+            Operation::Crop { .. } => "crop",
+            Operation::Rotate { .. } => "rotate",
+            Operation::Sharpen { .. } => "sharpen",
+            Operation::Strip => "strip",
+            Operation::Quality { .. } => "quality",
+            Operation::Format { .. } => "format",
+        }
+    }
+
+    /// Check the arguments of the operation without touching any image.
+    fn check(&self) -> Result<(), &'static str> {
+        match self {
+            Operation::Resize {
+                width,
+                height,
+                filter,
+                blur,
+            } => {
+                if *width == 0 || *height == 0 {
+                    return Err("width and height must be greater than zero");
+                }
+                if *filter == FilterTypes::Unknown {
+                    return Err("unknown filter");
+                }
+                if !blur.is_finite() || *blur <= 0. {
+                    return Err("blur must be a finite number greater than zero");
+                }
+            }
+            Operation::Crop { width, height, .. } => {
+                if *width == 0 || *height == 0 {
+                    return Err("width and height must be greater than zero");
+                }
+            }
+            Operation::Rotate {
+                degrees,
+                background,
+            } => {
+                if !degrees.is_finite() {
+                    return Err("degrees must be a finite number");
+                }
+                if background.parse::<Color>().is_err() {
+                    return Err("background must be a valid color");
+                }
+            }
+            Operation::Sharpen { radius, sigma } => {
+                if !radius.is_finite() || *radius < 0. {
+                    return Err("radius must be a finite, non-negative number");
+                }
+                if !sigma.is_finite() || *sigma <= 0. {
+                    return Err("sigma must be a finite number greater than zero");
+                }
+            }
+            Operation::Strip => {}
+            Operation::Quality { quality } => {
+                if *quality > 100 {
+                    return Err("quality must be in range 0..=100");
+                }
+            }
+            Operation::Format { format } => {
+                if format.is_empty() || format.contains('\0') {
+                    return Err("format must be a non-empty name without NUL bytes");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply the operation to the current image of `wand`.
+    fn apply(&self, wand: &mut MagickWand<'_>) -> crate::Result<()> {
+        match self {
+            Operation::Resize {
+                width,
+                height,
+                filter,
+                blur,
+            } => wand.resize_image(*width, *height, *filter, *blur)?,
+            Operation::Crop {
+                width,
+                height,
+                x,
+                y,
+            } => wand.crop_image(*width, *height, *x, *y)?,
+            Operation::Rotate {
+                degrees,
+                background,
+            } => {
+                let background: Color = background.parse().expect("validated background");
+                wand.rotate_image(background, *degrees)?
+            }
+            Operation::Sharpen { radius, sigma } => wand.sharpen_image(*radius, *sigma)?,
+            Operation::Strip => wand.strip_image()?,
+            Operation::Quality { quality } => wand.set_compression_quality(*quality)?,
+            Operation::Format { format } => wand.set_image_format(format.as_str())?,
+        };
+        Ok(())
+    }
+}
+
+/// Error returned by [Pipeline::validate].
+#[derive(ThisError, Debug, Clone, Eq, PartialEq)]
+#[error("invalid operation #{index} ({name}): {reason}")]
+pub struct ValidationError {
+    index: usize,
+    name: &'static str,
+    reason: &'static str,
+}
+
+impl ValidationError {
+    /// Index of the offending operation in the pipeline.
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
+    /// Name of the offending operation, see [Operation::name].
+    pub fn get_name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Why the operation was rejected.
+    pub fn get_reason(&self) -> &'static str {
+        self.reason
+    }
+}
+
+/// Ordered list of [Operation]s, applied one after another.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Pipeline {
+    operations: Vec<Operation>,
+}
+
+impl Pipeline {
+    /// Create an empty pipeline.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Append an operation.
+    pub fn push(mut self, operation: Operation) -> Self {
+        self.operations.push(operation);
+        self
+    }
+
+    /// Append a resize with `LanczosFilter` and no extra blur, see
+    /// [Pipeline::resize_with] to choose them.
+    pub fn resize(self, width: c_ulong, height: c_ulong) -> Self {
+        self.resize_with(width, height, FilterTypes::LanczosFilter, 1.)
+    }
+
+    /// Append a resize.
+    pub fn resize_with(
+        self,
+        width: c_ulong,
+        height: c_ulong,
+        filter: FilterTypes,
+        blur: c_double,
+    ) -> Self {
+        self.push(Operation::Resize {
+            width,
+            height,
+            filter,
+            blur,
+        })
+    }
+
+    /// Append a crop.
+    pub fn crop(self, width: c_ulong, height: c_ulong, x: c_long, y: c_long) -> Self {
+        self.push(Operation::Crop {
+            width,
+            height,
+            x,
+            y,
+        })
+    }
+
+    /// Append a rotation, filling the empty triangles with `background`.
+    pub fn rotate(self, degrees: c_double, background: impl Into<String>) -> Self {
+        self.push(Operation::Rotate {
+            degrees,
+            background: background.into(),
+        })
+    }
+
+    /// Append a sharpen.
+    pub fn sharpen(self, radius: c_double, sigma: c_double) -> Self {
+        self.push(Operation::Sharpen { radius, sigma })
+    }
+
+    /// Append a strip of all profiles and comments.
+    pub fn strip(self) -> Self {
+        self.push(Operation::Strip)
+    }
+
+    /// Append setting the compression quality used when writing.
+    pub fn quality(self, quality: c_ulong) -> Self {
+        self.push(Operation::Quality { quality })
+    }
+
+    /// Append setting the format used when writing.
+    pub fn format(self, format: impl Into<String>) -> Self {
+        self.push(Operation::Format {
+            format: format.into(),
+        })
+    }
+
+    /// Operations of the pipeline, in order.
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    /// Check every operation, without touching any image.
+    ///
+    /// # Return
+    ///
+    /// The first invalid operation, if any.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        for (index, operation) in self.operations.iter().enumerate() {
+            operation.check().map_err(|reason| ValidationError {
+                index,
+                name: operation.name(),
+                reason,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Validate the pipeline, then apply every operation to the current image
+    /// of `wand`.
+    ///
+    /// Nothing is applied if validation fails. If an operation fails in
+    /// GraphicsMagick, the operations before it have already been applied.
+    pub fn apply(&self, wand: &mut MagickWand<'_>) -> crate::Result<()> {
+        self.validate()?;
+        for operation in &self.operations {
+            operation.apply(wand)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Vec<Operation>> for Pipeline {
+    fn from(operations: Vec<Operation>) -> Self {
+        Pipeline { operations }
+    }
+}
+
+impl FromIterator<Operation> for Pipeline {
+    fn from_iter<I: IntoIterator<Item = Operation>>(iter: I) -> Self {
+        Pipeline {
+            operations: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::new_logo_magick_wand;
+
+    #[test]
+    fn test_pipeline_validate() {
+        let pipeline = Pipeline::new()
+            .resize(100, 100)
+            .crop(50, 50, 10, 10)
+            .rotate(90., "white")
+            .sharpen(0., 1.)
+            .strip()
+            .quality(85)
+            .format("PNG");
+        assert_eq!(pipeline.operations().len(), 7);
+        pipeline.validate().unwrap();
+
+        let err = Pipeline::new()
+            .resize(100, 100)
+            .quality(101)
+            .validate()
+            .unwrap_err();
+        assert_eq!(err.get_index(), 1);
+        assert_eq!(err.get_name(), "quality");
+
+        let err = Pipeline::new().resize(0, 100).validate().unwrap_err();
+        assert_eq!(err.get_index(), 0);
+        assert_eq!(err.get_name(), "resize");
+
+        assert!(Pipeline::new().sharpen(0., 0.).validate().is_err());
+        assert!(
+            Pipeline::new()
+                .rotate(f64::NAN, "white")
+                .validate()
+                .is_err()
+        );
+        assert!(Pipeline::new().format("").validate().is_err());
+
+        let err = Pipeline::new()
+            .rotate(90., "notacolor")
+            .validate()
+            .unwrap_err();
+        assert_eq!(err.get_name(), "rotate");
+        assert_eq!(err.get_reason(), "background must be a valid color");
+        assert!(Pipeline::new().rotate(90., "").validate().is_err());
+        assert!(Pipeline::new().rotate(90., "#ff000080").validate().is_ok());
+    }
+
+    #[test]
+    fn test_pipeline_apply() {
+        let pipeline = Pipeline::new()
+            .resize(200, 100)
+            .crop(100, 50, 0, 0)
+            .rotate(90., "white")
+            .sharpen(0., 1.)
+            .strip()
+            .quality(80)
+            .format("PNG");
+
+        for _ in 0..2 {
+            let mut mw = new_logo_magick_wand();
+            pipeline.apply(&mut mw).unwrap();
+            assert_eq!(mw.get_image_width(), 50);
+            assert_eq!(mw.get_image_height(), 100);
+            assert_eq!(mw.get_image_format().to_str().unwrap(), "PNG");
+        }
+    }
+
+    #[test]
+    fn test_pipeline_apply_invalid() {
+        let mut mw = new_logo_magick_wand();
+        let result = Pipeline::new()
+            .resize(100, 100)
+            .crop(0, 0, 0, 0)
+            .apply(&mut mw);
+        assert!(matches!(result, Err(crate::Error::Pipeline(_))));
+        // Nothing applied.
+        assert_eq!(mw.get_image_width(), 1024);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_pipeline_serde() {
+        let pipeline = Pipeline::new()
+            .resize(100, 100)
+            .rotate(45., "black")
+            .strip()
+            .format("JPEG");
+        let json = serde_json::to_string(&pipeline).unwrap();
+        let decoded: Pipeline = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, pipeline);
+
+        let decoded: Pipeline = serde_json::from_str(
+            r#"[{"op": "crop", "width": 10, "height": 10, "x": 0, "y": 0}, {"op": "strip"}]"#,
+        )
+        .unwrap();
+        assert_eq!(decoded, Pipeline::new().crop(10, 10, 0, 0).strip());
+    }
+}
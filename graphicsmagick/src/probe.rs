@@ -0,0 +1,228 @@
+//! Properties of an image read without decoding its pixels, e.g. to validate
+//! an upload before paying for its decoding.
+//!
+//! ```no_run
+//! use graphicsmagick::{initialize, probe::ImageProbe};
+//!
+//! initialize();
+//!
+//! let upload = std::fs::read("upload.jpg").unwrap();
+//! let probe = ImageProbe::from_bytes(&upload).unwrap();
+//! if probe.width * probe.height > 50_000_000 || probe.frames > 100 {
+//!     eprintln!("{} image too large", probe.format);
+//! }
+//! ```
+
+use crate::{
+    error::{Exception, ExceptionType, with_exception_info},
+    types::ColorspaceType,
+    utils::assert_initialized,
+};
+use graphicsmagick_sys::{
+    CloneImageInfo, DestroyImageInfo, DestroyImageList, ExceptionInfo, GetImageListLength, Image,
+    ImageInfo, MaxTextExtent, PingBlob, PingImage, size_t,
+};
+use null_terminated_str::IntoNullTerminatedString;
+use std::{
+    ffi::CStr,
+    os::raw::{c_char, c_uint, c_ulong},
+    ptr,
+};
+
+/// Properties of an image, read with GraphicsMagick's ping, which parses the
+/// header of every frame without decoding the pixels, nor allocating the
+/// pixel cache.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ImageProbe {
+    /// Format detected from the content, e.g. `"PNG"`.
+    pub format: String,
+    /// Width of the first frame, in pixels.
+    pub width: c_ulong,
+    /// Height of the first frame, in pixels.
+    pub height: c_ulong,
+    /// Number of frames, more than one for an animation or a multi-page
+    /// document.
+    pub frames: c_ulong,
+    /// Colorspace of the first frame.
+    pub colorspace: ColorspaceType,
+    /// Bits per channel of the first frame.
+    pub depth: c_uint,
+}
+
+impl ImageProbe {
+    /// Probe an encoded image, whose format is detected from its content.
+    pub fn from_bytes(bytes: &[u8]) -> crate::Result<Self> {
+        Self::ping(|image_info, exception| unsafe {
+            PingBlob(
+                image_info,
+                bytes.as_ptr().cast(),
+                bytes.len() as size_t,
+                exception,
+            )
+        })
+    }
+
+    /// Probe an image file, see [MagickWand::ping_image](crate::wand::MagickWand::ping_image).
+    pub fn from_path<'s>(path: impl IntoNullTerminatedString<'s>) -> crate::Result<Self> {
+        let path = path.into_null_terminated_string();
+        let filename = path.as_c_str().to_bytes_with_nul();
+        if filename.len() > MaxTextExtent as usize {
+            return Err(Exception::new(
+                ExceptionType::OptionError,
+                "file name is too long".to_owned(),
+                Some(path.as_c_str().to_string_lossy().into_owned()),
+            )
+            .into());
+        }
+        Self::ping(|image_info, exception| unsafe {
+            for (to, &from) in (*image_info).filename.iter_mut().zip(filename) {
+                *to = from as c_char;
+            }
+            PingImage(image_info, exception)
+        })
+    }
+
+    fn ping(
+        ping: impl FnOnce(*mut ImageInfo, *mut ExceptionInfo) -> *mut Image,
+    ) -> crate::Result<Self> {
+        assert_initialized();
+        let image_info = unsafe { CloneImageInfo(ptr::null()) };
+        if image_info.is_null() {
+            return Err(Exception::new(
+                ExceptionType::ResourceError,
+                "memory allocation failed".to_owned(),
+                None,
+            )
+            .into());
+        }
+        let result = with_exception_info(|exception| unsafe {
+            let image = ping(image_info, exception);
+            let Some(first) = image.as_ref() else {
+                return Err(Exception::from_exception_info(exception).into());
+            };
+            let probe = ImageProbe {
+                format: CStr::from_ptr(first.magick.as_ptr())
+                    .to_string_lossy()
+                    .into_owned(),
+                width: first.columns,
+                height: first.rows,
+                frames: GetImageListLength(image),
+                colorspace: first.colorspace.into(),
+                depth: first.depth,
+            };
+            DestroyImageList(image);
+            Ok(probe)
+        });
+        unsafe { DestroyImageInfo(image_info) };
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        animation::AnimationBuilder,
+        initialize,
+        tests::{logo_unicode_path, new_color_magick_wand},
+        types::DisposeType,
+        wand::MagickWand,
+    };
+    use std::time::{Duration, Instant};
+
+    fn crc32(bytes: &[u8]) -> u32 {
+        let mut crc = !0u32;
+        for &byte in bytes {
+            crc ^= u32::from(byte);
+            for _ in 0..8 {
+                crc = if crc & 1 == 1 {
+                    (crc >> 1) ^ 0xedb8_8320
+                } else {
+                    crc >> 1
+                };
+            }
+        }
+        !crc
+    }
+
+    /// Header of a `width` x `height` 8 bits RGB PNG, whose pixels are
+    /// missing.
+    fn png_header(width: u32, height: u32) -> Vec<u8> {
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        let mut chunk = |kind: &[u8], data: &[u8]| {
+            png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            let start = png.len();
+            png.extend_from_slice(kind);
+            png.extend_from_slice(data);
+            let crc = crc32(&png[start..]);
+            png.extend_from_slice(&crc.to_be_bytes());
+        };
+        let mut header = Vec::new();
+        header.extend_from_slice(&width.to_be_bytes());
+        header.extend_from_slice(&height.to_be_bytes());
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+        chunk(b"IHDR", &header);
+        chunk(b"IDAT", &[0x78, 0x9c]);
+        chunk(b"IEND", &[]);
+        png
+    }
+
+    #[test]
+    fn test_image_probe_from_path() {
+        initialize();
+        let probe = ImageProbe::from_path(logo_unicode_path()).unwrap();
+        assert_eq!(probe.format, "WEBP");
+        assert_eq!((probe.width, probe.height), (1024, 1024));
+        assert_eq!(probe.frames, 1);
+        assert_eq!(probe.depth, 8);
+
+        let e = ImageProbe::from_path("/nonexistent/image.png").unwrap_err();
+        assert!(e.exception().is_some());
+    }
+
+    #[test]
+    fn test_image_probe_from_bytes() {
+        initialize();
+        let mut mw = MagickWand::new();
+        mw.read_image(logo_unicode_path()).unwrap();
+        mw.set_image_format("PNG").unwrap();
+        let png = mw.write_image_blob().unwrap();
+        let probe = ImageProbe::from_bytes(&png).unwrap();
+        assert_eq!(probe.format, "PNG");
+        assert_eq!((probe.width, probe.height), (1024, 1024));
+        assert_eq!(probe.colorspace, ColorspaceType::RGBColorspace);
+
+        let gif = AnimationBuilder::new()
+            .frame(
+                new_color_magick_wand("red", 40, 30),
+                10,
+                DisposeType::NoneDispose,
+            )
+            .frame(
+                new_color_magick_wand("blue", 40, 30),
+                10,
+                DisposeType::NoneDispose,
+            )
+            .build()
+            .unwrap();
+        let probe = ImageProbe::from_bytes(&gif).unwrap();
+        assert_eq!(probe.format, "GIF");
+        assert_eq!((probe.width, probe.height, probe.frames), (40, 30, 2));
+
+        assert!(ImageProbe::from_bytes(b"not an image").is_err());
+        assert!(ImageProbe::from_bytes(&[]).is_err());
+    }
+
+    #[test]
+    fn test_image_probe_large() {
+        initialize();
+        // 400 megapixels, gigabytes of pixel cache if decoded.
+        let png = png_header(20_000, 20_000);
+        let start = Instant::now();
+        let probe = ImageProbe::from_bytes(&png).unwrap();
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(probe.format, "PNG");
+        assert_eq!((probe.width, probe.height), (20_000, 20_000));
+        assert_eq!(probe.depth, 8);
+    }
+}
@@ -0,0 +1,294 @@
+//! Typed resource limits, and resource usage of the pixel cache.
+//!
+//! The limits are global to the process, like
+//! [MagickWand::set_resource_limit](crate::wand::MagickWand::set_resource_limit),
+//! but in the units of each resource: bytes, pixels or a count.
+//!
+//! ```no_run
+//! use graphicsmagick::resource::{ResourceLimits, ResourceUsage, initialize_with_limits};
+//!
+//! initialize_with_limits(&ResourceLimits {
+//!     memory: Some(512 << 20),
+//!     pixels: Some(100_000_000),
+//!     ..Default::default()
+//! })
+//! .unwrap();
+//!
+//! {
+//!     // Stricter limits while handling an upload.
+//!     let _guard = ResourceLimits {
+//!         pixels: Some(25_000_000),
+//!         ..Default::default()
+//!     }
+//!     .apply_scoped()
+//!     .unwrap();
+//!     // ...
+//! }
+//!
+//! let usage = ResourceUsage::current();
+//! println!("pixel cache: {} bytes of memory, {} bytes on disk", usage.memory, usage.disk);
+//! ```
+
+use crate::{
+    error::{Exception, ExceptionType},
+    initialize,
+    types::ResourceType,
+    utils::assert_initialized,
+};
+use graphicsmagick_sys::{
+    GetMagickResource, GetMagickResourceLimit, MagickFail, SetMagickResourceLimit, magick_int64_t,
+};
+
+/// Limit meaning no limit.
+pub const UNLIMITED: u64 = magick_int64_t::MAX as u64;
+
+fn to_magick(value: u64) -> magick_int64_t {
+    magick_int64_t::try_from(value).unwrap_or(magick_int64_t::MAX)
+}
+
+fn from_magick(value: magick_int64_t) -> u64 {
+    u64::try_from(value).unwrap_or_default()
+}
+
+/// Limits of the resources GraphicsMagick may use, `None` leaving a limit
+/// unchanged when applied.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResourceLimits {
+    /// Heap memory of the pixel cache, in bytes.
+    pub memory: Option<u64>,
+    /// Memory-mapped files of the pixel cache, in bytes.
+    pub map: Option<u64>,
+    /// Disk space of the pixel cache, in bytes.
+    pub disk: Option<u64>,
+    /// Number of open pixel cache files.
+    pub files: Option<u64>,
+    /// Number of pixels of a single image.
+    pub pixels: Option<u64>,
+    /// Number of worker threads.
+    pub threads: Option<u64>,
+    /// Width of an image, in pixels.
+    #[cfg(feature = "v1_3_21")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "v1_3_21")))]
+    pub width: Option<u64>,
+    /// Height of an image, in pixels.
+    #[cfg(feature = "v1_3_21")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "v1_3_21")))]
+    pub height: Option<u64>,
+}
+
+impl ResourceLimits {
+    /// Limits in effect, [UNLIMITED] for the resources without limit.
+    pub fn current() -> Self {
+        assert_initialized();
+        let limit = |resource: ResourceType| {
+            Some(from_magick(unsafe {
+                GetMagickResourceLimit(resource.into())
+            }))
+        };
+        ResourceLimits {
+            memory: limit(ResourceType::MemoryResource),
+            map: limit(ResourceType::MapResource),
+            disk: limit(ResourceType::DiskResource),
+            files: limit(ResourceType::FileResource),
+            pixels: limit(ResourceType::PixelsResource),
+            threads: limit(ResourceType::ThreadsResource),
+            #[cfg(feature = "v1_3_21")]
+            width: limit(ResourceType::WidthResource),
+            #[cfg(feature = "v1_3_21")]
+            height: limit(ResourceType::HeightResource),
+        }
+    }
+
+    /// Set the limits which are not `None`.
+    ///
+    /// The limits set before an error stay set.
+    pub fn apply(&self) -> crate::Result<()> {
+        assert_initialized();
+        for (resource, limit) in self.limits() {
+            let Some(limit) = limit else {
+                continue;
+            };
+            let status = unsafe { SetMagickResourceLimit(resource.into(), to_magick(limit)) };
+            if status == MagickFail {
+                return Err(Exception::new(
+                    ExceptionType::ResourceError,
+                    "can't set resource limit".to_owned(),
+                    Some(format!("{:?}: {}", resource, limit)),
+                )
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Set the limits which are not `None` until the returned guard is
+    /// dropped, which restores their previous value.
+    ///
+    /// As the limits are global, they apply to every thread meanwhile, and
+    /// nested guards must be dropped in the reverse order of their creation.
+    pub fn apply_scoped(&self) -> crate::Result<ResourceLimitsGuard> {
+        let mut previous = ResourceLimits::current();
+        for ((_, limit), (_, previous)) in self.limits().into_iter().zip(previous.limits_mut()) {
+            if limit.is_none() {
+                *previous = None;
+            }
+        }
+        // Created first, to restore the limits set before an error.
+        let guard = ResourceLimitsGuard { previous };
+        self.apply()?;
+        Ok(guard)
+    }
+
+    /// Each limit with its resource.
+    fn limits(&self) -> Vec<(ResourceType, Option<u64>)> {
+        let mut limits = *self;
+        limits
+            .limits_mut()
+            .into_iter()
+            .map(|(resource, limit)| (resource, *limit))
+            .collect()
+    }
+
+    fn limits_mut(&mut self) -> Vec<(ResourceType, &mut Option<u64>)> {
+        #[allow(unused_mut)]
+        let mut limits = vec![
+            (ResourceType::MemoryResource, &mut self.memory),
+            (ResourceType::MapResource, &mut self.map),
+            (ResourceType::DiskResource, &mut self.disk),
+            (ResourceType::FileResource, &mut self.files),
+            (ResourceType::PixelsResource, &mut self.pixels),
+            (ResourceType::ThreadsResource, &mut self.threads),
+        ];
+        #[cfg(feature = "v1_3_21")]
+        limits.extend([
+            (ResourceType::WidthResource, &mut self.width),
+            (ResourceType::HeightResource, &mut self.height),
+        ]);
+        limits
+    }
+}
+
+/// Guard of [ResourceLimits::apply_scoped], restoring the previous limits on
+/// drop.
+#[derive(Debug)]
+#[must_use = "the previous limits are restored when the guard is dropped"]
+pub struct ResourceLimitsGuard {
+    previous: ResourceLimits,
+}
+
+impl ResourceLimitsGuard {
+    /// Limits restored on drop, `None` for the ones left unchanged.
+    pub fn previous(&self) -> &ResourceLimits {
+        &self.previous
+    }
+}
+
+impl Drop for ResourceLimitsGuard {
+    fn drop(&mut self) {
+        // The previous limits were accepted once.
+        let _ = self.previous.apply();
+    }
+}
+
+/// Resources in use, mostly by the pixel cache.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResourceUsage {
+    /// Heap memory, in bytes.
+    pub memory: u64,
+    /// Memory-mapped files, in bytes.
+    pub map: u64,
+    /// Disk space, in bytes.
+    pub disk: u64,
+    /// Number of open files.
+    pub files: u64,
+}
+
+impl ResourceUsage {
+    /// Resources in use by the whole process.
+    pub fn current() -> Self {
+        assert_initialized();
+        let usage =
+            |resource: ResourceType| from_magick(unsafe { GetMagickResource(resource.into()) });
+        ResourceUsage {
+            memory: usage(ResourceType::MemoryResource),
+            map: usage(ResourceType::MapResource),
+            disk: usage(ResourceType::DiskResource),
+            files: usage(ResourceType::FileResource),
+        }
+    }
+}
+
+/// [initialize], then [apply](ResourceLimits::apply) `limits`, before any
+/// image is allocated.
+pub fn initialize_with_limits(limits: &ResourceLimits) -> crate::Result<()> {
+    initialize();
+    limits.apply()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::logo_unicode_path, wand::MagickWand};
+
+    #[test]
+    fn test_resource_magick_int64() {
+        assert_eq!(to_magick(1 << 20), 1 << 20);
+        assert_eq!(to_magick(u64::MAX), magick_int64_t::MAX);
+        assert_eq!(from_magick(-1), 0);
+        assert_eq!(from_magick(to_magick(UNLIMITED)), UNLIMITED);
+    }
+
+    // The limits are global and shared with the tests running in parallel,
+    // so they are only changed by this test, and only raised far beyond what
+    // the other tests use: terabytes of disk, and more open files.
+    #[test]
+    fn test_resource_limits_apply_scoped() {
+        initialize_with_limits(&ResourceLimits::default()).unwrap();
+        let before = ResourceLimits::current();
+        assert!(before.disk.is_some() && before.files.is_some());
+        let more_files = before
+            .files
+            .map(|files| files.saturating_add(64).min(UNLIMITED));
+
+        {
+            let limits = ResourceLimits {
+                disk: Some(3 << 40),
+                ..Default::default()
+            };
+            let guard = limits.apply_scoped().unwrap();
+            assert_eq!(guard.previous().disk, before.disk);
+            assert_eq!(guard.previous().memory, None);
+            assert_eq!(ResourceLimits::current().disk, Some(3 << 40));
+            assert_eq!(ResourceLimits::current().memory, before.memory);
+
+            {
+                let _guard = ResourceLimits {
+                    disk: Some(2 << 40),
+                    files: more_files,
+                    ..Default::default()
+                }
+                .apply_scoped()
+                .unwrap();
+                let current = ResourceLimits::current();
+                assert_eq!(current.disk, Some(2 << 40));
+                assert_eq!(current.files, more_files);
+            }
+            assert_eq!(ResourceLimits::current().disk, Some(3 << 40));
+            assert_eq!(ResourceLimits::current().files, before.files);
+        }
+        assert_eq!(ResourceLimits::current(), before);
+    }
+
+    #[test]
+    fn test_resource_usage_current() {
+        crate::initialize();
+        let mut mw = MagickWand::new();
+        mw.read_image(logo_unicode_path()).unwrap();
+        let usage = ResourceUsage::current();
+        // At least the pixels of the image, in memory or mapped.
+        assert!(usage.memory + usage.map >= 1024 * 1024);
+        drop(mw);
+    }
+}
@@ -0,0 +1,339 @@
+//! Cropping around the most interesting region of an image, rather than its
+//! center.
+//!
+//! ```no_run
+//! use graphicsmagick::{initialize, smart_crop::Strategy, wand::MagickWand};
+//!
+//! initialize();
+//!
+//! let mut mw = MagickWand::new();
+//! mw.read_image("landscape.jpg").unwrap();
+//! let size = mw.get_image_height();
+//! // A square avatar out of a landscape photo.
+//! mw.smart_crop(size, size, Strategy::Attention).unwrap();
+//! ```
+
+use crate::{
+    error::{Exception, ExceptionType},
+    geometry::Geometry,
+    wand::MagickWand,
+};
+use std::{
+    ops::{Add, Sub},
+    os::raw::{c_long, c_ulong},
+};
+
+/// How [MagickWand::smart_crop] chooses the region to keep.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Strategy {
+    /// The region whose gray levels have the highest Shannon entropy, i.e.
+    /// the most detailed one. Flat areas, such as the sky, score low.
+    Entropy,
+    /// The region with the most edges, see [MagickWand::edge_image], and the
+    /// most saturated colors, which tend to draw the eye.
+    Attention,
+}
+
+/// Longest side of the copy the regions are scored on.
+const ANALYSIS_SIZE: c_ulong = 256;
+
+/// Number of gray levels of the histograms of [Strategy::Entropy].
+const BINS: usize = 32;
+
+/// Summed-area table of a grid of values, to sum any rectangle of it in
+/// constant time.
+#[derive(Debug)]
+struct SummedArea<T> {
+    /// Width of the grid plus one, the first row and column being zeros.
+    stride: usize,
+    sums: Vec<T>,
+}
+
+impl<T: Copy + Default + Add<Output = T> + Sub<Output = T>> SummedArea<T> {
+    /// Table of the row-major `values` of a grid `width` wide.
+    fn new(values: impl IntoIterator<Item = T>, width: usize) -> Self {
+        let stride = width + 1;
+        let mut sums = vec![T::default(); stride];
+        let mut row = T::default();
+        for (i, value) in values.into_iter().enumerate() {
+            if i % width == 0 {
+                sums.push(T::default());
+                row = T::default();
+            }
+            row = row + value;
+            let above = sums[sums.len() - stride];
+            sums.push(above + row);
+        }
+        SummedArea { stride, sums }
+    }
+
+    /// Sum of the `width` x `height` rectangle at `(x, y)`.
+    fn sum(&self, x: usize, y: usize, width: usize, height: usize) -> T {
+        let at = |x: usize, y: usize| self.sums[y * self.stride + x];
+        at(x + width, y + height) + at(x, y) - at(x + width, y) - at(x, y + height)
+    }
+}
+
+/// Shannon entropy, in bits, of a histogram.
+fn entropy(histogram: impl Iterator<Item = u32> + Clone) -> f64 {
+    let total: u32 = histogram.clone().sum();
+    if total == 0 {
+        return 0.;
+    }
+    histogram
+        .filter(|&count| count > 0)
+        .map(|count| {
+            let p = count as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Offset of the `window` sized region of a `size` grid with the highest
+/// `score`, the one closest to the center among equal scores.
+fn best_window(
+    size: (usize, usize),
+    window: (usize, usize),
+    score: impl Fn(usize, usize) -> f64,
+) -> (usize, usize) {
+    let (free_x, free_y) = (size.0 - window.0, size.1 - window.1);
+    // Doubled, to stay in integers.
+    let distance =
+        |x: usize, y: usize| (2 * x).abs_diff(free_x).pow(2) + (2 * y).abs_diff(free_y).pow(2);
+    let mut best = (f64::NEG_INFINITY, 0, 0);
+    for y in 0..=free_y {
+        for x in 0..=free_x {
+            let score = score(x, y);
+            if score > best.0 || (score == best.0 && distance(x, y) < distance(best.1, best.2)) {
+                best = (score, x, y);
+            }
+        }
+    }
+    (best.1, best.2)
+}
+
+/// `length` of a side of `full` pixels, on the same side of `scaled` pixels.
+fn rescale(length: usize, full: usize, scaled: usize) -> usize {
+    ((length * scaled + full / 2) / full).clamp(1, scaled)
+}
+
+impl MagickWand<'_> {
+    /// Crop the current image to `width` x `height` pixels around its most
+    /// interesting region according to `strategy`, instead of its center.
+    ///
+    /// The regions are scored on a copy of at most 256x256 pixels, so that the
+    /// cost hardly depends on the size of the image. A size larger than the
+    /// image is reduced to the size of the image.
+    pub fn smart_crop(
+        &mut self,
+        width: c_ulong,
+        height: c_ulong,
+        strategy: Strategy,
+    ) -> crate::Result<&mut Self> {
+        if width == 0 || height == 0 {
+            return Err(Exception::new(
+                ExceptionType::OptionError,
+                "crop size is empty".to_owned(),
+                None,
+            )
+            .into());
+        }
+        let (columns, rows) = (self.get_image_width(), self.get_image_height());
+        let (width, height) = (width.min(columns), height.min(rows));
+        let (x, y) = if (width, height) == (columns, rows) {
+            (0, 0)
+        } else {
+            self.smart_crop_offset(width, height, strategy)?
+        };
+        self.crop_image(width, height, x, y)?;
+        // Drop the offset left by cropping.
+        self.set_image_page(0, 0, 0, 0)
+    }
+
+    /// Offset of the `width` x `height` region kept by
+    /// [MagickWand::smart_crop], which must fit in the current image.
+    fn smart_crop_offset(
+        &mut self,
+        width: c_ulong,
+        height: c_ulong,
+        strategy: Strategy,
+    ) -> crate::Result<(c_long, c_long)> {
+        let (columns, rows) = (self.get_image_width(), self.get_image_height());
+        let Some(mut analysis) = self.get_image() else {
+            return Err(unsafe { self.get_error() });
+        };
+        let (analysis_columns, analysis_rows) = Geometry::new(ANALYSIS_SIZE, ANALYSIS_SIZE)
+            .shrink_only()
+            .dimensions(columns, rows);
+        analysis.scale_image(analysis_columns, analysis_rows)?;
+
+        let (columns, rows) = (columns as usize, rows as usize);
+        let size = (analysis_columns as usize, analysis_rows as usize);
+        let window = (
+            rescale(width as usize, columns, size.0),
+            rescale(height as usize, rows, size.1),
+        );
+        let (x, y) = match strategy {
+            Strategy::Entropy => {
+                let gray =
+                    analysis.get_image_pixels::<u8>(0, 0, analysis_columns, analysis_rows, "I")?;
+                let histograms: Vec<SummedArea<u32>> = (0..BINS)
+                    .map(|bin| {
+                        let in_bin = gray
+                            .iter()
+                            .map(|&level| u32::from(usize::from(level) * BINS / 256 == bin));
+                        SummedArea::new(in_bin, size.0)
+                    })
+                    .collect();
+                best_window(size, window, |x, y| {
+                    entropy(
+                        histograms
+                            .iter()
+                            .map(|histogram| histogram.sum(x, y, window.0, window.1)),
+                    )
+                })
+            }
+            Strategy::Attention => {
+                let colors = analysis.get_image_pixels::<u8>(
+                    0,
+                    0,
+                    analysis_columns,
+                    analysis_rows,
+                    "RGB",
+                )?;
+                analysis.edge_image(0.)?;
+                let edges =
+                    analysis.get_image_pixels::<u8>(0, 0, analysis_columns, analysis_rows, "I")?;
+                let saliency = edges
+                    .iter()
+                    .zip(colors.chunks_exact(3))
+                    .map(|(&edge, rgb)| {
+                        let max = rgb.iter().max().copied().unwrap_or_default();
+                        let min = rgb.iter().min().copied().unwrap_or_default();
+                        (f64::from(edge) + f64::from(max - min)) / 255.
+                    });
+                let saliency = SummedArea::new(saliency, size.0);
+                best_window(size, window, |x, y| saliency.sum(x, y, window.0, window.1))
+            }
+        };
+
+        // Back to the full size, keeping the region in the image.
+        let x = (x * columns + size.0 / 2) / size.0;
+        let y = (y * rows + size.1 / 2) / size.1;
+        let x = x.min(columns - width as usize);
+        let y = y.min(rows - height as usize);
+        Ok((x as c_long, y as c_long))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{initialize, wand::magick::MagickWandImportSlice};
+
+    const STRATEGIES: [Strategy; 2] = [Strategy::Entropy, Strategy::Attention];
+
+    /// White image with blocks of pseudo-random colors in the `detail`
+    /// columns.
+    fn new_magick_wand(
+        columns: c_ulong,
+        rows: c_ulong,
+        detail: std::ops::Range<c_ulong>,
+    ) -> MagickWand<'static> {
+        initialize();
+        let mut state = 0x9e37_79b9_u32;
+        let mut blocks = Vec::new();
+        for _ in 0..(columns / 8 + 1) * (rows / 8 + 1) * 3 {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            blocks.push(state as u8);
+        }
+        let mut pixels = Vec::new();
+        for y in 0..rows {
+            for x in 0..columns {
+                let block = ((y / 8) * (columns / 8 + 1) + x / 8) as usize * 3;
+                if detail.contains(&x) {
+                    pixels.extend_from_slice(&blocks[block..block + 3]);
+                } else {
+                    pixels.extend_from_slice(&[255, 255, 255]);
+                }
+            }
+        }
+
+        let mut mw = MagickWand::new();
+        mw.set_size(columns, rows).unwrap();
+        mw.read_image("xc:white").unwrap();
+        let input = MagickWandImportSlice::new(columns, rows, "RGB", &pixels).unwrap();
+        mw.set_image_pixels(0, 0, input).unwrap();
+        mw
+    }
+
+    #[test]
+    fn test_smart_crop_summed_area() {
+        let values: Vec<u32> = (0..12).collect();
+        let area = SummedArea::new(values.iter().copied(), 4);
+        assert_eq!(area.sum(0, 0, 4, 3), 66);
+        assert_eq!(area.sum(1, 1, 2, 2), 5 + 6 + 9 + 10);
+        assert_eq!(area.sum(3, 0, 1, 3), 3 + 7 + 11);
+        assert_eq!(area.sum(2, 2, 0, 1), 0);
+    }
+
+    #[test]
+    fn test_smart_crop_entropy() {
+        assert_eq!(entropy([10, 0, 0].into_iter()), 0.);
+        assert_eq!(entropy([5, 5].into_iter()), 1.);
+        assert_eq!(entropy([1, 1, 1, 1].into_iter()), 2.);
+        assert_eq!(entropy([0, 0].into_iter()), 0.);
+    }
+
+    #[test]
+    fn test_smart_crop_best_window() {
+        // Peak at column 7.
+        let score = |x: usize, _: usize| -(x as f64 - 7.).abs();
+        assert_eq!(best_window((20, 5), (5, 5), score), (7, 0));
+        // Flat: centered.
+        assert_eq!(best_window((20, 10), (6, 4), |_, _| 0.), (7, 3));
+    }
+
+    #[test]
+    fn test_smart_crop_offset() {
+        for strategy in STRATEGIES {
+            let mut mw = new_magick_wand(600, 200, 400..600);
+            let (x, y) = mw.smart_crop_offset(200, 200, strategy).unwrap();
+            assert!(x >= 390, "{:?}: {}", strategy, x);
+            assert_eq!(y, 0);
+
+            let mut mw = new_magick_wand(256, 512, 0..0);
+            assert_eq!(mw.smart_crop_offset(256, 256, strategy).unwrap(), (0, 128));
+        }
+    }
+
+    #[test]
+    fn test_smart_crop() {
+        for strategy in STRATEGIES {
+            let mut mw = new_magick_wand(600, 200, 0..150);
+            mw.smart_crop(200, 200, strategy).unwrap();
+            assert_eq!(mw.get_image_width(), 200);
+            assert_eq!(mw.get_image_height(), 200);
+            assert_eq!(mw.get_image_page().unwrap(), (0, 0, 0, 0));
+            // The detailed columns are kept.
+            let left = mw.get_image_pixels::<u8>(0, 100, 1, 1, "RGB").unwrap();
+            assert_ne!(left, [255, 255, 255]);
+
+            // Larger than the image.
+            mw.smart_crop(300, 100, strategy).unwrap();
+            assert_eq!(mw.get_image_width(), 200);
+            assert_eq!(mw.get_image_height(), 100);
+        }
+
+        let mut mw = new_magick_wand(60, 20, 0..0);
+        let e = mw.smart_crop(0, 10, Strategy::Entropy).unwrap_err();
+        assert_eq!(
+            e.exception().map(Exception::get_exception_type),
+            Some(ExceptionType::OptionError)
+        );
+    }
+}
@@ -0,0 +1,274 @@
+//! Visual regression helpers, comparing images with golden files.
+//!
+//! Requires the `testing` feature, typically enabled for the
+//! dev-dependency only.
+//!
+//! ```no_run
+//! use graphicsmagick::{initialize, testing::Golden, types::MetricType, wand::MagickWand};
+//!
+//! initialize();
+//!
+//! let mut mw = MagickWand::new();
+//! mw.read_image("input.png").unwrap();
+//! mw.blur_image(0., 2.).unwrap();
+//!
+//! Golden::new("tests/golden/blur.png")
+//!     .tolerance(MetricType::RootMeanSquaredErrorMetric, 0.001)
+//!     .assert_matches(&mw);
+//! ```
+//!
+//! When the image doesn't match, `blur.actual.png` and `blur.diff.png` (see
+//! [CompareReport::difference]) are written next to the golden file, or in
+//! [Golden::output_dir]. Renaming the actual image to the golden file
+//! accepts the change.
+
+use crate::{compare::CompareReport, types::MetricType, wand::MagickWand};
+use std::{
+    fs,
+    os::raw::c_double,
+    path::{Path, PathBuf},
+};
+
+/// Golden file an image must match, see [Golden::assert_matches].
+#[derive(Debug, Clone)]
+pub struct Golden {
+    path: PathBuf,
+    metric: MetricType,
+    tolerance: c_double,
+    output_dir: Option<PathBuf>,
+}
+
+impl Golden {
+    /// Golden file at `path`, which must match exactly unless a
+    /// [Golden::tolerance] is set.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Golden {
+            path: path.into(),
+            metric: MetricType::RootMeanSquaredErrorMetric,
+            tolerance: 0.,
+            output_dir: None,
+        }
+    }
+
+    /// Accept a distortion within `tolerance` for `metric`, see
+    /// [MetricDistortion::is_within](crate::compare::MetricDistortion::is_within).
+    pub fn tolerance(mut self, metric: MetricType, tolerance: c_double) -> Self {
+        self.metric = metric;
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Directory where the actual and difference images are written on
+    /// failure, the directory of the golden file by default.
+    pub fn output_dir(mut self, output_dir: impl Into<PathBuf>) -> Self {
+        self.output_dir = Some(output_dir.into());
+        self
+    }
+
+    /// Path of the actual image, `<golden stem>.actual.png`.
+    pub fn actual_path(&self) -> PathBuf {
+        self.output_path("actual")
+    }
+
+    /// Path of the difference image, `<golden stem>.diff.png`.
+    pub fn diff_path(&self) -> PathBuf {
+        self.output_path("diff")
+    }
+
+    fn output_path(&self, kind: &str) -> PathBuf {
+        let stem = self.path.file_stem().unwrap_or_default().to_string_lossy();
+        let dir = match &self.output_dir {
+            Some(dir) => dir,
+            None => self.path.parent().unwrap_or(Path::new("")),
+        };
+        dir.join(format!("{}.{}.png", stem, kind))
+    }
+
+    /// Compare the current image of `actual`, encoded as PNG like the golden
+    /// file, with the golden file.
+    ///
+    /// # Panic
+    ///
+    /// Panic if the golden file is missing, or if the images differ beyond
+    /// the tolerance, after writing the actual and difference images. The
+    /// images left by a previous failure are removed on success.
+    pub fn assert_matches(&self, actual: &MagickWand<'_>) -> CompareReport {
+        // Cloned, not to change the format of the image under test.
+        let actual_png = encode_png(&mut actual.clone());
+        let mut actual = MagickWand::new();
+        if let Err(e) = actual.read_image_blob(&actual_png) {
+            panic!("can't decode the actual image: {}", e);
+        }
+        let actual_path = self.actual_path();
+
+        let golden = match fs::read(&self.path) {
+            Ok(golden) => golden,
+            Err(e) => {
+                write_file(&actual_path, &actual_png);
+                panic!(
+                    "can't read golden file {}: {}, actual image written to {}",
+                    self.path.display(),
+                    e,
+                    actual_path.display()
+                );
+            }
+        };
+        let mut expected = MagickWand::new();
+        if let Err(e) = expected.read_image_blob(&golden) {
+            panic!("can't decode golden file {}: {}", self.path.display(), e);
+        }
+
+        let report = match actual.compare_report(&mut expected, &[self.metric]) {
+            Ok(report) => report,
+            Err(e) => {
+                write_file(&actual_path, &actual_png);
+                panic!(
+                    "image doesn't match golden file {}: {}, actual image written to {}",
+                    self.path.display(),
+                    e,
+                    actual_path.display()
+                );
+            }
+        };
+
+        let diff_path = self.diff_path();
+        if report.is_within(self.metric, self.tolerance) {
+            for path in [actual_path, diff_path] {
+                let _ = fs::remove_file(path);
+            }
+            return report;
+        }
+
+        write_file(&actual_path, &actual_png);
+        let mut difference = report.difference().clone();
+        write_file(&diff_path, &encode_png(&mut difference));
+        panic!(
+            "image doesn't match golden file {} within {} {:?}: {}, actual image written to {}, \
+             difference to {}",
+            self.path.display(),
+            self.tolerance,
+            self.metric,
+            report,
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
+
+/// Shorthand of [Golden::assert_matches] for an exact match.
+pub fn assert_matches_golden(actual: &MagickWand<'_>, path: impl Into<PathBuf>) {
+    Golden::new(path).assert_matches(actual);
+}
+
+fn encode_png(mw: &mut MagickWand<'_>) -> Vec<u8> {
+    if let Err(e) = mw.set_image_format("PNG") {
+        panic!("can't encode as PNG: {}", e);
+    }
+    match mw.write_image_blob() {
+        Some(blob) => blob.to_vec(),
+        None => panic!("can't encode as PNG: {}", unsafe { mw.get_error() }),
+    }
+}
+
+fn write_file(path: &Path, contents: &[u8]) {
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    if let Err(e) = fs::write(path, contents) {
+        panic!("can't write {}: {}", path.display(), e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::new_logo_magick_wand, types::FilterTypes};
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+
+    /// The logo, small enough to keep the golden files light.
+    fn new_small_logo_magick_wand() -> MagickWand<'static> {
+        let mut mw = new_logo_magick_wand();
+        mw.resize_image(64, 64, FilterTypes::LanczosFilter, 1.)
+            .unwrap();
+        mw
+    }
+
+    fn new_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "graphicsmagick-testing-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_golden_output_paths() {
+        let golden = Golden::new("tests/golden/blur.png");
+        assert_eq!(
+            golden.actual_path(),
+            Path::new("tests/golden/blur.actual.png")
+        );
+        let golden = golden.output_dir("target/failures");
+        assert_eq!(
+            golden.diff_path(),
+            Path::new("target/failures/blur.diff.png")
+        );
+    }
+
+    #[test]
+    fn test_golden_assert_matches() {
+        let dir = new_dir("matches");
+        let path = dir.join("logo.png");
+        let mut mw = new_small_logo_magick_wand();
+        write_file(&path, &encode_png(&mut mw.clone()));
+
+        let report = Golden::new(&path).assert_matches(&mw);
+        assert_eq!(report.changed_pixels(), 0);
+        assert_matches_golden(&mw, &path);
+        assert!(!Golden::new(&path).actual_path().exists());
+
+        mw.blur_image(0., 1.).unwrap();
+        let golden = Golden::new(&path).tolerance(MetricType::PeakSignalToNoiseRatioMetric, 10.);
+        golden.assert_matches(&mw);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_golden_assert_matches_failure() {
+        let dir = new_dir("failure");
+        let path = dir.join("logo.png");
+        let mut mw = new_small_logo_magick_wand();
+        write_file(&path, &encode_png(&mut mw.clone()));
+        mw.blur_image(0., 4.).unwrap();
+
+        let golden = Golden::new(&path);
+        let result = catch_unwind(AssertUnwindSafe(|| golden.assert_matches(&mw)));
+        assert!(result.is_err());
+        assert!(golden.actual_path().exists());
+        assert!(golden.diff_path().exists());
+
+        // Accepting the change.
+        fs::rename(golden.actual_path(), &path).unwrap();
+        golden.assert_matches(&mw);
+        assert!(!golden.diff_path().exists());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_golden_assert_matches_missing() {
+        let dir = new_dir("missing");
+        let golden = Golden::new(dir.join("missing.png"));
+        let mw = new_small_logo_magick_wand();
+
+        let result = catch_unwind(AssertUnwindSafe(|| golden.assert_matches(&mw)));
+        assert!(result.is_err());
+        assert!(golden.actual_path().exists());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -1,3 +1,6 @@
+use crate::{initialize, wand::MagickWand};
+use std::os::raw::c_ulong;
+
 pub(crate) fn logo_path() -> String {
     let mut path = std::env::var("PWD").unwrap();
     path.push_str("/meta/GraphicsMagick-Logo.webp");
@@ -9,3 +12,25 @@ pub(crate) fn logo_unicode_path() -> String {
     path.push_str("/meta/GraphicsMagick-图标.webp");
     path
 }
+
+/// Wand holding the logo, after initializing GraphicsMagick.
+pub(crate) fn new_logo_magick_wand() -> MagickWand<'static> {
+    initialize();
+    let mut mw = MagickWand::new();
+    mw.read_image(logo_unicode_path()).unwrap();
+    mw
+}
+
+/// Wand holding a `width`x`height` image filled with `color`, after
+/// initializing GraphicsMagick.
+pub(crate) fn new_color_magick_wand(
+    color: &str,
+    width: c_ulong,
+    height: c_ulong,
+) -> MagickWand<'static> {
+    initialize();
+    let mut mw = MagickWand::new();
+    mw.set_size(width, height).unwrap();
+    mw.read_image(format!("xc:{}", color).as_str()).unwrap();
+    mw
+}
@@ -0,0 +1,550 @@
+//! Thumbnails of a given size, fitting, filling or padded to it.
+//!
+//! ```no_run
+//! use graphicsmagick::{
+//!     color::Color,
+//!     initialize,
+//!     thumbnail::{ThumbnailMode, ThumbnailOptions},
+//!     types::GravityType,
+//!     wand::MagickWand,
+//! };
+//!
+//! initialize();
+//!
+//! let mut mw = MagickWand::new();
+//! mw.read_image("photo.jpg").unwrap();
+//! // A 128x128 avatar, cropped around the top of the photo.
+//! mw.thumbnail_with_options(
+//!     128,
+//!     128,
+//!     ThumbnailMode::Fill,
+//!     GravityType::NorthGravity,
+//!     ThumbnailOptions::new().strip(true),
+//! )
+//! .unwrap();
+//! mw.write_image("avatar.jpg").unwrap();
+//!
+//! let mut mw = MagickWand::new();
+//! mw.read_image("banner.gif").unwrap();
+//! // Every frame letterboxed in 640x360.
+//! mw.thumbnail(
+//!     640,
+//!     360,
+//!     ThumbnailMode::Pad(Color::rgb(0, 0, 0)),
+//!     GravityType::CenterGravity,
+//! )
+//! .unwrap();
+//! ```
+
+use crate::{
+    color::Color,
+    error::{Exception, ExceptionType},
+    geometry::Geometry,
+    types::{FilterTypes, GravityType},
+    wand::MagickWand,
+};
+use std::os::raw::{c_long, c_ulong};
+
+/// How [MagickWand::thumbnail] fits the image in the requested size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThumbnailMode {
+    /// Resize to fit in the size, keeping the aspect ratio: one side is
+    /// shorter than requested unless the aspect ratios match.
+    Fit,
+    /// Resize to cover the size, keeping the aspect ratio, then crop the
+    /// overflowing side at the gravity, to get exactly the size.
+    Fill,
+    /// Resize to cover the size, keeping the aspect ratio, without cropping:
+    /// one side is longer than requested unless the aspect ratios match.
+    Cover,
+    /// Resize to fit in the size like [ThumbnailMode::Fit], then extend the
+    /// shorter side with the color, placing the image at the gravity, to get
+    /// exactly the size.
+    Pad(Color),
+}
+
+/// Options of [MagickWand::thumbnail_with_options].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThumbnailOptions {
+    upscale: bool,
+    strip: bool,
+    filter: FilterTypes,
+}
+
+impl Default for ThumbnailOptions {
+    fn default() -> Self {
+        ThumbnailOptions {
+            upscale: false,
+            strip: false,
+            filter: FilterTypes::LanczosFilter,
+        }
+    }
+}
+
+impl ThumbnailOptions {
+    /// Options never enlarging the image, keeping its metadata and resizing
+    /// with [FilterTypes::LanczosFilter].
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Whether images smaller than the requested size are enlarged.
+    ///
+    /// Otherwise they are left at their size, and only padded by
+    /// [ThumbnailMode::Pad], so that [ThumbnailMode::Fill] may give a smaller
+    /// image than requested.
+    pub fn upscale(mut self, upscale: bool) -> Self {
+        self.upscale = upscale;
+        self
+    }
+
+    /// Whether the profiles and comments are removed, see
+    /// [MagickWand::strip_image].
+    pub fn strip(mut self, strip: bool) -> Self {
+        self.strip = strip;
+        self
+    }
+
+    /// Set the filter of [MagickWand::resize_image].
+    pub fn filter(mut self, filter: FilterTypes) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
+/// Operations making the thumbnail of a `columns` x `rows` image.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct Plan {
+    resize: (c_ulong, c_ulong),
+    /// `(width, height, x, y)` of the region kept out of the resized image.
+    crop: Option<(c_ulong, c_ulong, c_long, c_long)>,
+    /// `(width, height, x, y)` of the canvas, and where the resized image is
+    /// placed on it.
+    extent: Option<(c_ulong, c_ulong, c_long, c_long)>,
+}
+
+impl Plan {
+    fn new(
+        (columns, rows): (c_ulong, c_ulong),
+        (width, height): (c_ulong, c_ulong),
+        mode: ThumbnailMode,
+        gravity: GravityType,
+        upscale: bool,
+    ) -> Self {
+        let mut geometry = Geometry::new(width, height);
+        if matches!(mode, ThumbnailMode::Fill | ThumbnailMode::Cover) {
+            geometry = geometry.fill();
+        }
+        if !upscale {
+            geometry = geometry.shrink_only();
+        }
+        let resize = geometry.dimensions(columns, rows);
+
+        let mut plan = Plan {
+            resize,
+            crop: None,
+            extent: None,
+        };
+        match mode {
+            ThumbnailMode::Fit | ThumbnailMode::Cover => {}
+            ThumbnailMode::Fill => {
+                let region = (resize.0.min(width), resize.1.min(height));
+                if region != resize {
+                    let (x, y) = gravity_offset(gravity, resize, region);
+                    plan.crop = Some((region.0, region.1, x, y));
+                }
+            }
+            ThumbnailMode::Pad(_) => {
+                if resize != (width, height) {
+                    let (x, y) = gravity_offset(gravity, (width, height), resize);
+                    plan.extent = Some((width, height, x, y));
+                }
+            }
+        }
+        plan
+    }
+}
+
+/// Offset of a region of size `inner` placed at `gravity` in a region of size
+/// `outer`, the center for [GravityType::ForgetGravity] and
+/// [GravityType::StaticGravity].
+fn gravity_offset(
+    gravity: GravityType,
+    outer: (c_ulong, c_ulong),
+    inner: (c_ulong, c_ulong),
+) -> (c_long, c_long) {
+    let free_x = outer.0 as c_long - inner.0 as c_long;
+    let free_y = outer.1 as c_long - inner.1 as c_long;
+    let x = match gravity {
+        GravityType::NorthWestGravity
+        | GravityType::WestGravity
+        | GravityType::SouthWestGravity => 0,
+        GravityType::NorthEastGravity
+        | GravityType::EastGravity
+        | GravityType::SouthEastGravity => free_x,
+        _ => free_x / 2,
+    };
+    let y = match gravity {
+        GravityType::NorthWestGravity
+        | GravityType::NorthGravity
+        | GravityType::NorthEastGravity => 0,
+        GravityType::SouthWestGravity
+        | GravityType::SouthGravity
+        | GravityType::SouthEastGravity => free_y,
+        _ => free_y / 2,
+    };
+    (x, y)
+}
+
+impl MagickWand<'_> {
+    /// [MagickWand::thumbnail_with_options] with the default
+    /// [ThumbnailOptions].
+    pub fn thumbnail(
+        &mut self,
+        width: c_ulong,
+        height: c_ulong,
+        mode: ThumbnailMode,
+        gravity: GravityType,
+    ) -> crate::Result<&mut Self> {
+        self.thumbnail_with_options(width, height, mode, gravity, ThumbnailOptions::new())
+    }
+
+    /// Make a thumbnail of `width` x `height` pixels out of every image, see
+    /// [ThumbnailMode] for how the aspect ratio is kept, `gravity` being the
+    /// part of the image kept by [ThumbnailMode::Fill], or where the image is
+    /// placed by [ThumbnailMode::Pad].
+    ///
+    /// The images are first rotated according to their EXIF orientation,
+    /// which is then reset. An animation is coalesced first, see
+    /// [MagickWand::coalesce_images], so that every frame is the size of the
+    /// thumbnail.
+    pub fn thumbnail_with_options(
+        &mut self,
+        width: c_ulong,
+        height: c_ulong,
+        mode: ThumbnailMode,
+        gravity: GravityType,
+        options: ThumbnailOptions,
+    ) -> crate::Result<&mut Self> {
+        if width == 0 || height == 0 {
+            return Err(Exception::new(
+                ExceptionType::OptionError,
+                "thumbnail size is empty".to_owned(),
+                None,
+            )
+            .into());
+        }
+
+        let count = self.get_number_images();
+        if count <= 1 {
+            self.thumbnail_image(width, height, mode, gravity, options)?;
+            return Ok(self);
+        }
+
+        // The coalesced sequence borrows `self`, so the thumbnails are moved
+        // to a wand of their own before replacing the images of `self`.
+        let mut thumbnails = MagickWand::new();
+        {
+            let Some(mut coalesced) = self.coalesce_images() else {
+                return Err(unsafe { self.get_error() });
+            };
+            for index in 0..count {
+                coalesced.set_image_index(index as c_long)?;
+                coalesced.thumbnail_image(width, height, mode, gravity, options)?;
+            }
+            coalesced.reset_iterator();
+            thumbnails.add_image(&coalesced)?;
+        }
+        while self.get_number_images() > 0 {
+            self.set_image_index(0)?;
+            self.remove_image()?;
+        }
+        self.add_image(&thumbnails)?;
+        self.reset_iterator();
+        Ok(self)
+    }
+
+    /// Make the thumbnail of the current image.
+    fn thumbnail_image(
+        &mut self,
+        width: c_ulong,
+        height: c_ulong,
+        mode: ThumbnailMode,
+        gravity: GravityType,
+        options: ThumbnailOptions,
+    ) -> crate::Result<()> {
+        self.orient_from_exif()?;
+
+        let size = (self.get_image_width(), self.get_image_height());
+        let plan = Plan::new(size, (width, height), mode, gravity, options.upscale);
+        if plan.resize != size {
+            self.resize_image(plan.resize.0, plan.resize.1, options.filter, 1.)?;
+        }
+        if let Some((width, height, x, y)) = plan.crop {
+            self.crop_image(width, height, x, y)?;
+        }
+        if let (Some((width, height, x, y)), ThumbnailMode::Pad(color)) = (plan.extent, mode) {
+            self.set_image_background_color(color)?;
+            self.extent_image(width as _, height as _, x as _, y as _)?;
+        }
+        // Drop the offset left by cropping, and the canvas of a coalesced
+        // frame.
+        self.set_image_page(0, 0, 0, 0)?;
+
+        if options.strip {
+            self.strip_image()?;
+        }
+        Ok(())
+    }
+
+    /// Rotate the current image to the top-left orientation, and reset the
+    /// orientation of its EXIF profile accordingly, not to be rotated twice.
+    #[cfg(feature = "v1_3_26")]
+    fn orient_from_exif(&mut self) -> crate::Result<()> {
+        use crate::types::OrientationType;
+
+        let orientation = self.get_image_orientation();
+        if !matches!(
+            orientation,
+            OrientationType::UndefinedOrientation
+                | OrientationType::TopLeftOrientation
+                | OrientationType::Unknown
+        ) {
+            self.auto_orient_image(orientation)?;
+        }
+        self.reset_exif_orientation()
+    }
+
+    /// Rotate the current image to the top-left orientation, and reset the
+    /// orientation of its EXIF profile accordingly, not to be rotated twice.
+    #[cfg(not(feature = "v1_3_26"))]
+    fn orient_from_exif(&mut self) -> crate::Result<()> {
+        // A broken profile has no orientation.
+        let Some(orientation) = self
+            .get_exif()
+            .ok()
+            .flatten()
+            .and_then(|exif| exif.orientation())
+        else {
+            return Ok(());
+        };
+        // Right angles, so the background isn't visible.
+        let background = Color::transparent();
+        match orientation {
+            2 => self.flop_image()?,
+            3 => self.rotate_image(background, 180.)?,
+            4 => self.flip_image()?,
+            5 => self.rotate_image(background, 90.)?.flop_image()?,
+            6 => self.rotate_image(background, 90.)?,
+            7 => self.rotate_image(background, 270.)?.flop_image()?,
+            8 => self.rotate_image(background, 270.)?,
+            _ => return Ok(()),
+        };
+        self.reset_exif_orientation()
+    }
+
+    /// Set the orientation of the EXIF profile to top-left, in place, see
+    /// [MagickWand::set_exif_orientation]. A broken profile is left as is.
+    fn reset_exif_orientation(&mut self) -> crate::Result<()> {
+        match self.set_exif_orientation(1) {
+            Err(crate::Error::Exif(_)) => Ok(()),
+            result => result.map(drop),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        animation::AnimationBuilder,
+        exif::{ByteOrder, Exif},
+        tests::new_color_magick_wand,
+        types::DisposeType,
+    };
+
+    fn size(mw: &mut MagickWand<'_>) -> (c_ulong, c_ulong) {
+        (mw.get_image_width(), mw.get_image_height())
+    }
+
+    fn pixel(mw: &mut MagickWand<'_>, x: c_long, y: c_long) -> Vec<u8> {
+        mw.get_image_pixels::<u8>(x, y, 1, 1, "RGB").unwrap()
+    }
+
+    #[test]
+    fn test_thumbnail_plan() {
+        let center = GravityType::CenterGravity;
+        let plan =
+            |size, mode, gravity, upscale| Plan::new(size, (100, 100), mode, gravity, upscale);
+
+        let fit = plan((400, 200), ThumbnailMode::Fit, center, false);
+        assert_eq!(fit.resize, (100, 50));
+        assert_eq!((fit.crop, fit.extent), (None, None));
+        assert_eq!(
+            plan((40, 20), ThumbnailMode::Fit, center, false).resize,
+            (40, 20)
+        );
+        assert_eq!(
+            plan((40, 20), ThumbnailMode::Fit, center, true).resize,
+            (100, 50)
+        );
+
+        let cover = plan((400, 200), ThumbnailMode::Cover, center, false);
+        assert_eq!(cover.resize, (200, 100));
+        assert_eq!(cover.crop, None);
+
+        let fill = plan((400, 200), ThumbnailMode::Fill, center, false);
+        assert_eq!(fill.resize, (200, 100));
+        assert_eq!(fill.crop, Some((100, 100, 50, 0)));
+        let fill = plan(
+            (200, 400),
+            ThumbnailMode::Fill,
+            GravityType::SouthGravity,
+            false,
+        );
+        assert_eq!(fill.crop, Some((100, 100, 0, 100)));
+        let fill = plan(
+            (400, 50),
+            ThumbnailMode::Fill,
+            GravityType::EastGravity,
+            false,
+        );
+        assert_eq!(fill.resize, (400, 50));
+        assert_eq!(fill.crop, Some((100, 50, 300, 0)));
+
+        let pad = ThumbnailMode::Pad(Color::transparent());
+        assert_eq!(
+            plan((400, 200), pad, center, false).extent,
+            Some((100, 100, 0, 25))
+        );
+        let padded = plan((40, 20), pad, GravityType::NorthWestGravity, false);
+        assert_eq!(padded.resize, (40, 20));
+        assert_eq!(padded.extent, Some((100, 100, 0, 0)));
+        assert_eq!(plan((400, 400), pad, center, false).extent, None);
+    }
+
+    #[test]
+    fn test_thumbnail_gravity_offset() {
+        let offset = |gravity| gravity_offset(gravity, (100, 50), (20, 10));
+        assert_eq!(offset(GravityType::NorthWestGravity), (0, 0));
+        assert_eq!(offset(GravityType::NorthGravity), (40, 0));
+        assert_eq!(offset(GravityType::EastGravity), (80, 20));
+        assert_eq!(offset(GravityType::SouthWestGravity), (0, 40));
+        assert_eq!(offset(GravityType::CenterGravity), (40, 20));
+        assert_eq!(offset(GravityType::ForgetGravity), (40, 20));
+    }
+
+    #[test]
+    fn test_thumbnail_modes() {
+        let center = GravityType::CenterGravity;
+        let mut mw = new_color_magick_wand("red", 400, 200);
+        mw.thumbnail(100, 100, ThumbnailMode::Fit, center).unwrap();
+        assert_eq!(size(&mut mw), (100, 50));
+
+        let mut mw = new_color_magick_wand("red", 400, 200);
+        mw.thumbnail(100, 100, ThumbnailMode::Cover, center)
+            .unwrap();
+        assert_eq!(size(&mut mw), (200, 100));
+
+        let mut mw = new_color_magick_wand("red", 400, 200);
+        mw.thumbnail(100, 100, ThumbnailMode::Fill, center).unwrap();
+        assert_eq!(size(&mut mw), (100, 100));
+        assert_eq!(mw.get_image_page().unwrap(), (0, 0, 0, 0));
+
+        let mut mw = new_color_magick_wand("red", 400, 200);
+        let pad = ThumbnailMode::Pad(Color::rgb(0, 0, 255));
+        mw.thumbnail(100, 100, pad, center).unwrap();
+        assert_eq!(size(&mut mw), (100, 100));
+        assert_eq!(pixel(&mut mw, 50, 5), [0, 0, 255]);
+        assert_eq!(pixel(&mut mw, 50, 50), [255, 0, 0]);
+
+        // Not enlarged unless asked.
+        let mut mw = new_color_magick_wand("red", 40, 20);
+        mw.thumbnail(100, 100, ThumbnailMode::Fill, center).unwrap();
+        assert_eq!(size(&mut mw), (40, 20));
+        let options = ThumbnailOptions::new().upscale(true);
+        mw.thumbnail_with_options(100, 100, ThumbnailMode::Fill, center, options)
+            .unwrap();
+        assert_eq!(size(&mut mw), (100, 100));
+
+        let e = mw
+            .thumbnail(0, 100, ThumbnailMode::Fit, center)
+            .unwrap_err();
+        assert_eq!(
+            e.exception().map(Exception::get_exception_type),
+            Some(ExceptionType::OptionError)
+        );
+    }
+
+    #[test]
+    fn test_thumbnail_exif_orientation() {
+        let mut mw = new_color_magick_wand("red", 200, 100);
+        let mut exif = Exif::new(ByteOrder::LittleEndian);
+        exif.set_orientation(6);
+        mw.set_exif(&exif).unwrap();
+        mw.set_image_format("JPEG").unwrap();
+        let blob = mw.write_image_blob().unwrap();
+        let mut mw = MagickWand::new();
+        mw.read_image_blob(&blob).unwrap();
+
+        mw.thumbnail(100, 100, ThumbnailMode::Fit, GravityType::CenterGravity)
+            .unwrap();
+        assert_eq!(size(&mut mw), (50, 100));
+        assert_eq!(mw.get_exif().unwrap().unwrap().orientation(), Some(1));
+
+        let options = ThumbnailOptions::new().strip(true);
+        mw.thumbnail_with_options(
+            100,
+            100,
+            ThumbnailMode::Fit,
+            GravityType::CenterGravity,
+            options,
+        )
+        .unwrap();
+        assert_eq!(size(&mut mw), (50, 100));
+        assert!(mw.get_exif().unwrap().is_none());
+
+        // A broken profile is ignored, and kept.
+        let mut mw = new_color_magick_wand("red", 200, 100);
+        mw.set_image_profile_bytes("EXIF", b"garbage").unwrap();
+        mw.thumbnail(100, 100, ThumbnailMode::Fit, GravityType::CenterGravity)
+            .unwrap();
+        assert_eq!(size(&mut mw), (100, 50));
+        assert_eq!(&*mw.get_image_profile_bytes("EXIF").unwrap(), b"garbage");
+    }
+
+    #[test]
+    fn test_thumbnail_animation() {
+        let gif = AnimationBuilder::new()
+            .frame(
+                new_color_magick_wand("red", 400, 200),
+                10,
+                DisposeType::NoneDispose,
+            )
+            .frame(
+                new_color_magick_wand("blue", 400, 200),
+                10,
+                DisposeType::NoneDispose,
+            )
+            .frame(
+                new_color_magick_wand("lime", 400, 200),
+                10,
+                DisposeType::NoneDispose,
+            )
+            .build()
+            .unwrap();
+        let mut mw = MagickWand::new();
+        mw.read_image_blob(&gif).unwrap();
+
+        mw.thumbnail(100, 100, ThumbnailMode::Fill, GravityType::CenterGravity)
+            .unwrap();
+        assert_eq!(mw.get_number_images(), 3);
+        let mut colors = Vec::new();
+        for index in 0..3 {
+            mw.set_image_index(index).unwrap();
+            assert_eq!(size(&mut mw), (100, 100));
+            colors.push(pixel(&mut mw, 50, 50));
+        }
+        assert_eq!(colors, [[255, 0, 0], [0, 0, 255], [0, 255, 0]]);
+    }
+}
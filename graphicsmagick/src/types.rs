@@ -49,6 +49,7 @@ pub enum ChannelType {
 ///
 /// <http://www.graphicsmagick.org/api/types.html#filtertypes>
 #[derive(Debug, Eq, PartialEq, Copy, Clone, IntoPrimitive, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u32)]
 pub enum FilterTypes {
     /// Unset value.
@@ -8,3 +8,8 @@ pub use rgb::{MaxRGB, max_rgb};
 mod magick_alloc;
 pub(crate) use magick_alloc::{CStrExt, MagickAutoRelinquish};
 pub use magick_alloc::{MagickBoxSlice, MagickCString};
+
+#[cfg(unix)]
+mod stdio;
+#[cfg(unix)]
+pub(crate) use stdio::{read_pipe, write_pipe};
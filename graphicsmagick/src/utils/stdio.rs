@@ -0,0 +1,97 @@
+use graphicsmagick_sys::FILE;
+use std::{
+    ffi::CStr,
+    fs::File,
+    io,
+    os::fd::{AsRawFd, FromRawFd, IntoRawFd, OwnedFd},
+    ptr::NonNull,
+};
+
+/// A stdio `FILE` handle over one end of a pipe, closed on drop.
+#[derive(Debug)]
+pub(crate) struct StdioFile(NonNull<FILE>);
+
+impl StdioFile {
+    fn from_fd(fd: OwnedFd, mode: &CStr) -> io::Result<Self> {
+        let file = unsafe { libc::fdopen(fd.as_raw_fd(), mode.as_ptr()) };
+        match NonNull::new(file) {
+            Some(file) => {
+                // The descriptor is owned by the `FILE` from now on.
+                let _ = fd.into_raw_fd();
+                Ok(Self(file.cast()))
+            }
+            None => Err(io::Error::last_os_error()),
+        }
+    }
+
+    pub(crate) fn as_ptr(&self) -> *mut FILE {
+        self.0.as_ptr()
+    }
+
+    /// Read and discard the rest of the input, until the write end of the
+    /// pipe is closed.
+    pub(crate) fn drain(&self) -> io::Result<()> {
+        let file = self.0.as_ptr().cast();
+        let mut buf = [0u8; 8192];
+        // The EOF or error flag left by the coder would end `fread` early.
+        unsafe { libc::clearerr(file) };
+        loop {
+            let n = unsafe { libc::fread(buf.as_mut_ptr().cast(), 1, buf.len(), file) };
+            if n == 0 {
+                return if unsafe { libc::ferror(file) } != 0 {
+                    Err(io::Error::last_os_error())
+                } else {
+                    Ok(())
+                };
+            }
+        }
+    }
+
+    /// Flush the buffered output and close the descriptor, reporting the
+    /// errors `Drop` has to ignore.
+    pub(crate) fn close(self) -> io::Result<()> {
+        let status = unsafe { libc::fclose(self.0.as_ptr().cast()) };
+        std::mem::forget(self);
+        if status == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+}
+
+impl Drop for StdioFile {
+    fn drop(&mut self) {
+        unsafe {
+            libc::fclose(self.0.as_ptr().cast());
+        }
+    }
+}
+
+fn pipe() -> io::Result<(OwnedFd, OwnedFd)> {
+    let mut fds = [0; 2];
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let (read, write) = unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+    // Delegates forked by GraphicsMagick mustn't inherit the write end, or the
+    // read end would never see EOF.
+    for fd in [&read, &write] {
+        if unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok((read, write))
+}
+
+/// Open a pipe, whose read end is a `FILE` for GraphicsMagick to decode from.
+pub(crate) fn read_pipe() -> io::Result<(StdioFile, File)> {
+    let (read, write) = pipe()?;
+    Ok((StdioFile::from_fd(read, c"rb")?, File::from(write)))
+}
+
+/// Open a pipe, whose write end is a `FILE` for GraphicsMagick to encode to.
+pub(crate) fn write_pipe() -> io::Result<(File, StdioFile)> {
+    let (read, write) = pipe()?;
+    Ok((File::from(read), StdioFile::from_fd(write, c"wb")?))
+}
@@ -0,0 +1,310 @@
+//! [MagickWand] for the tokio runtime.
+
+use crate::{types::FilterTypes, wand::MagickWand};
+use std::{
+    any::Any,
+    os::raw::{c_double, c_ulong},
+    panic,
+    sync::{Arc, Mutex, PoisonError},
+};
+use tokio::task::{self, JoinHandle};
+
+/// Result of an operation, type erased to be kept across calls.
+type Output = Box<dyn Any + Send>;
+
+/// Wrapper of [MagickWand] which runs every operation on the blocking thread
+/// pool of tokio, so that decoding, processing and encoding images don't
+/// block the executor.
+///
+/// The wand is shared with the blocking thread for the duration of the
+/// operation, so it can't be borrowed by the result: methods return `()` or
+/// owned data instead of `&mut Self`. Use [`AsyncMagickWand::run`] for the
+/// methods of [MagickWand] which aren't wrapped here.
+///
+/// # Cancellation
+///
+/// If the future of an operation is dropped before completion, e.g. by
+/// `tokio::select!` or `tokio::time::timeout`, the operation still runs to
+/// completion on the blocking thread pool, and the next call waits for it
+/// first, so the wand stays usable and the operations stay in order.
+///
+/// ```no_run
+/// use graphicsmagick::{initialize, types::FilterTypes, wand::AsyncMagickWand};
+///
+/// # async fn thumbnail(blob: Vec<u8>) -> graphicsmagick::Result<Vec<u8>> {
+/// initialize();
+///
+/// let mut mw = AsyncMagickWand::new();
+/// mw.read_image_blob(blob).await?;
+/// mw.resize_image(128, 128, FilterTypes::LanczosFilter, 1.).await?;
+/// mw.set_image_format("PNG").await?;
+/// mw.write_image_blob().await
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct AsyncMagickWand {
+    wand: Arc<Mutex<MagickWand<'static>>>,
+    /// Operation whose future was dropped before its completion.
+    pending: Option<JoinHandle<Output>>,
+}
+
+impl AsyncMagickWand {
+    /// Construct an empty wand.
+    ///
+    /// # Panic
+    ///
+    /// Panic if [`initialize`](crate::initialize) hasn't been called.
+    pub fn new() -> Self {
+        MagickWand::new().into()
+    }
+
+    /// Run `f` with the wand on the blocking thread pool, once the operation
+    /// whose future was dropped, if any, has completed.
+    ///
+    /// # Errors
+    ///
+    /// [Cancelled](crate::Error::Cancelled) if the blocking task was
+    /// cancelled before running `f`, i.e. the runtime is shutting down.
+    ///
+    /// # Panic
+    ///
+    /// Resume the panic of `f`, if any.
+    pub async fn run<F, R>(&mut self, f: F) -> crate::Result<R>
+    where
+        F: FnOnce(&mut MagickWand<'static>) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        self.wait_pending().await;
+
+        let wand = self.wand.clone();
+        // Kept until completion, for the next call to wait for it if this
+        // future is dropped meanwhile.
+        let handle = self.pending.insert(task::spawn_blocking(move || {
+            // A panic of a previous operation leaves the wand usable.
+            let mut wand = wand.lock().unwrap_or_else(PoisonError::into_inner);
+            Box::new(f(&mut wand)) as Output
+        }));
+        let result = handle.await;
+        self.pending = None;
+
+        match result {
+            Ok(output) => Ok(*output
+                .downcast::<R>()
+                .expect("the output is the result of `f`")),
+            Err(e) if e.is_panic() => panic::resume_unwind(e.into_panic()),
+            Err(_) => Err(crate::Error::Cancelled),
+        }
+    }
+
+    /// Wait for the operation whose future was dropped, if any, ignoring its
+    /// result as nobody waits for it anymore.
+    async fn wait_pending(&mut self) {
+        if let Some(pending) = &mut self.pending {
+            let _ = pending.await;
+            self.pending = None;
+        }
+    }
+
+    /// See [`MagickWand::read_image`].
+    pub async fn read_image(&mut self, filename: impl Into<String>) -> crate::Result<()> {
+        let filename = filename.into();
+        self.run(move |wand| wand.read_image(filename).map(drop))
+            .await?
+    }
+
+    /// See [`MagickWand::read_image_blob`].
+    pub async fn read_image_blob(&mut self, blob: impl Into<Vec<u8>>) -> crate::Result<()> {
+        let blob = blob.into();
+        self.run(move |wand| wand.read_image_transient_blob(&blob).map(drop))
+            .await?
+    }
+
+    /// See [`MagickWand::resize_image`].
+    pub async fn resize_image(
+        &mut self,
+        columns: c_ulong,
+        rows: c_ulong,
+        filter: FilterTypes,
+        blur: c_double,
+    ) -> crate::Result<()> {
+        self.run(move |wand| wand.resize_image(columns, rows, filter, blur).map(drop))
+            .await?
+    }
+
+    /// See [`MagickWand::set_image_format`].
+    pub async fn set_image_format(&mut self, format: impl Into<String>) -> crate::Result<()> {
+        let format = format.into();
+        self.run(move |wand| wand.set_image_format(format).map(drop))
+            .await?
+    }
+
+    /// See [`MagickWand::write_image`].
+    pub async fn write_image(&mut self, filename: impl Into<String>) -> crate::Result<()> {
+        let filename = filename.into();
+        self.run(move |wand| wand.write_image(filename).map(drop))
+            .await?
+    }
+
+    /// See [`MagickWand::write_image_blob`].
+    pub async fn write_image_blob(&mut self) -> crate::Result<Vec<u8>> {
+        self.run(|wand| {
+            wand.write_image_blob()
+                .map(|blob| blob.to_vec())
+                .ok_or_else(|| unsafe { wand.get_error() })
+        })
+        .await?
+    }
+
+    /// Get the wrapped wand back, once the operation whose future was
+    /// dropped, if any, has completed.
+    pub async fn into_inner(mut self) -> MagickWand<'static> {
+        self.wait_pending().await;
+        // The blocking tasks drop their clone before completing.
+        let wand = Arc::try_unwrap(self.wand).expect("no operation is running");
+        wand.into_inner().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Default for AsyncMagickWand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<MagickWand<'static>> for AsyncMagickWand {
+    fn from(wand: MagickWand<'static>) -> Self {
+        AsyncMagickWand {
+            wand: Arc::new(Mutex::new(wand)),
+            pending: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{initialize, tests::logo_path};
+    use std::{fs, future, sync::Arc, thread, time::Duration};
+    use tokio::task::JoinSet;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_async_magick_wand() {
+        initialize();
+
+        let mut mw = AsyncMagickWand::new();
+        mw.read_image(logo_path()).await.unwrap();
+        mw.resize_image(100, 50, FilterTypes::LanczosFilter, 1.)
+            .await
+            .unwrap();
+        let size = mw
+            .run(|wand| (wand.get_image_width(), wand.get_image_height()))
+            .await
+            .unwrap();
+        assert_eq!(size, (100, 50));
+
+        assert!(mw.read_image("/not/exists/logo.png").await.is_err());
+
+        let mut mw = mw.into_inner().await;
+        assert_eq!(mw.get_image_width(), 100);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_async_magick_wand_dropped_operation() {
+        initialize();
+
+        let mut mw = AsyncMagickWand::new();
+        mw.read_image(logo_path()).await.unwrap();
+
+        let resize = mw.run(|wand| {
+            thread::sleep(Duration::from_millis(100));
+            wand.resize_image(100, 50, FilterTypes::LanczosFilter, 1.)
+                .map(drop)
+        });
+        tokio::select! {
+            biased;
+            _ = resize => panic!("the resize completed"),
+            _ = future::ready(()) => {}
+        }
+
+        // The resize completed before the next operation.
+        let size = mw
+            .run(|wand| (wand.get_image_width(), wand.get_image_height()))
+            .await
+            .unwrap();
+        assert_eq!(size, (100, 50));
+
+        let resize = mw.resize_image(10, 10, FilterTypes::LanczosFilter, 1.);
+        tokio::select! {
+            biased;
+            _ = resize => {}
+            _ = future::ready(()) => {}
+        }
+        let mut mw = mw.into_inner().await;
+        assert_eq!(mw.get_image_width(), 10);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_async_magick_wand_concurrent() {
+        initialize();
+
+        let blob = Arc::new(fs::read(logo_path()).unwrap());
+        let gif = {
+            let mut mw = MagickWand::new();
+            mw.set_size(8, 8).unwrap();
+            for color in ["xc:red", "xc:green", "xc:blue"] {
+                mw.read_image(color).unwrap();
+            }
+            mw.reset_iterator();
+            mw.set_image_format("GIF").unwrap();
+            Arc::new(mw.write_image_blob().unwrap().to_vec())
+        };
+        let mut tasks = JoinSet::new();
+        for _ in 0..8 {
+            let gif = gif.clone();
+            tasks.spawn(async move {
+                let mut mw = AsyncMagickWand::new();
+                mw.read_image_blob(gif.as_slice()).await?;
+                mw.run(|wand| wand.reset_iterator()).await?;
+                let gif = mw.write_image_blob().await?;
+
+                let mut mw = AsyncMagickWand::new();
+                mw.read_image_blob(gif).await?;
+                let frames = mw.run(|wand| wand.get_number_images()).await?;
+                crate::Result::Ok((3, frames))
+            });
+        }
+        for i in 0..32 {
+            let blob = blob.clone();
+            tasks.spawn(async move {
+                let size = 16 + i;
+                let mut mw = AsyncMagickWand::new();
+                mw.read_image_blob(blob.as_slice()).await?;
+                mw.resize_image(size, size, FilterTypes::TriangleFilter, 1.)
+                    .await?;
+                mw.set_image_format("PNG").await?;
+                let png = mw.write_image_blob().await?;
+
+                let mut mw = AsyncMagickWand::new();
+                mw.read_image_blob(png).await?;
+                let width = mw.run(|wand| wand.get_image_width()).await?;
+                crate::Result::Ok((size, width))
+            });
+        }
+
+        let mut count = 0;
+        while let Some(result) = tasks.join_next().await {
+            let (expected, actual) = result.unwrap().unwrap();
+            assert_eq!(expected, actual);
+            count += 1;
+        }
+        assert_eq!(count, 40);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "boom")]
+    async fn test_async_magick_wand_run_panic() {
+        initialize();
+        let mut mw = AsyncMagickWand::new();
+        mw.run(|_| panic!("boom")).await.unwrap()
+    }
+}
@@ -33,6 +33,14 @@ impl Drop for DrawingWand {
     }
 }
 
+// Safety:
+//
+// A `DrawingWand` owns its MVG buffer, graphic context stack and the
+// scratch image it renders into, and never consults thread-local state, so
+// it may be moved to another thread. It is not `Sync` because drawing
+// methods mutate the graphic context through a `*mut` pointer.
+unsafe impl Send for DrawingWand {}
+
 impl Clone for DrawingWand {
     fn clone(&self) -> Self {
         DrawingWand {
@@ -1652,12 +1660,29 @@ mod tests {
         },
         wand::{DrawingWand, PixelWand},
     };
+    use std::thread;
 
     fn new_logo_drawing_wand() -> DrawingWand {
         initialize();
         DrawingWand::new()
     }
 
+    #[test]
+    fn test_drawing_wand_send() {
+        let mut dw = new_logo_drawing_wand();
+        dw.set_stroke_width(2.);
+
+        let dw = thread::spawn(move || {
+            let mut dw = dw;
+            dw.line(0., 0., 10., 10.);
+            dw
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(dw.get_stroke_width(), 2.);
+    }
+
     #[test]
     fn test_drawing_wand_annotation() {
         new_logo_drawing_wand().annotation(0., 0., "");
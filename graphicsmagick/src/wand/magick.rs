@@ -4,11 +4,12 @@
 
 use crate::{
     MagickBoxSlice, MagickCString,
-    error::Exception,
+    color::AsPixelWand,
+    error::{self, Exception, ExceptionSeverity, with_exception_info},
     types::{
         ChannelType, ColorspaceType, CompositeOperator, CompressionType, DisposeType, FilterTypes,
-        ImageType, InterlaceType, MetricType, MontageMode, NoiseType, PreviewType, Quantum,
-        RenderingIntent, ResolutionType, ResourceType, VirtualPixelMethod,
+        ImageType, InterlaceType, MetricType, MontageMode, NoiseType, PixelPacket, PreviewType,
+        Quantum, RenderingIntent, ResolutionType, ResourceType, VirtualPixelMethod,
     },
     utils::{CStrExt, MagickAutoRelinquish, assert_initialized},
     wand::{DrawingWand, PixelWand},
@@ -18,12 +19,17 @@ use null_terminated_str::{IntoNullTerminatedString, NullTerminatedStr};
 use std::{
     borrow::Cow,
     ffi::CStr,
+    io::{Read, Write},
     marker::PhantomData,
-    mem::MaybeUninit,
+    mem::{self, MaybeUninit},
+    ops::{Deref, DerefMut, Range},
     os::raw::{c_double, c_float, c_long, c_uchar, c_uint, c_ulong, c_ushort, c_void},
     ptr::NonNull,
 };
 
+#[cfg(unix)]
+use std::{io, panic, thread};
+
 #[cfg(feature = "v1_3_26")]
 use crate::types::OrientationType;
 
@@ -61,9 +67,9 @@ def_magickwand_export_type!(StorageType_DoublePixel, c_double);
 
 /// Wrapper of `graphicsmagick_sys::MagickWand`.
 #[derive(Debug)]
-#[repr(transparent)]
 pub struct MagickWand<'a> {
     wand: NonNull<graphicsmagick_sys::MagickWand>,
+    warnings: Vec<Exception>,
     phantom: PhantomData<&'a [u8]>,
 }
 
@@ -81,34 +87,112 @@ impl MagickWand<'_> {
 
         MagickWand {
             wand,
+            warnings: Vec::new(),
             phantom: PhantomData,
         }
     }
 
+    /// Check the status of a call, clearing the warning it may have left on
+    /// the wand, so that it isn't collected by the next coder call instead.
     #[inline]
     fn check_status(&mut self, status: c_uint) -> crate::Result<&mut Self> {
         if status == MagickPass {
+            self.clear_exception();
+            Ok(self)
+        } else {
+            Err(unsafe { self.get_error() })
+        }
+    }
+
+    /// Like [MagickWand::check_status], but also collects the warning of a
+    /// successful call, for the calls running a coder or a color transform,
+    /// which are the ones warning in practice.
+    ///
+    /// `MagickGetException` allocates and formats the message even without
+    /// exception, so the other calls don't pay for it.
+    fn check_coder_status(&mut self, status: c_uint) -> crate::Result<&mut Self> {
+        if status == MagickPass {
+            unsafe { self.collect_warning() };
             Ok(self)
         } else {
             Err(unsafe { self.get_error() })
         }
     }
 
-    unsafe fn get_error(&mut self) -> crate::Error {
+    /// Fail with a `WandError` if the wand has no image, for the methods
+    /// which would otherwise take an empty wand for an image without the
+    /// data they look for.
+    pub(crate) fn check_has_images(&mut self) -> crate::Result<()> {
+        if self.get_number_images() == 0 {
+            return Err(Exception::new(
+                error::ExceptionType::WandError,
+                "wand contains no images".to_owned(),
+                None,
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Extract the exception of a failed call, then clear it, so that a
+    /// later failure isn't reported with this stale exception.
+    pub(crate) unsafe fn get_error(&mut self) -> crate::Error {
+        let exception = unsafe { self.get_exception() };
+        self.clear_exception();
+        exception.into()
+    }
+
+    unsafe fn get_exception(&mut self) -> Exception {
         unsafe {
             let mut severity: ExceptionType = 0;
 
-            let description_ptr =
+            let message_ptr =
                 MagickGetException(self.wand.as_ptr(), &mut severity as *mut ExceptionType);
 
-            let description = MagickAutoRelinquish::new(description_ptr as *mut c_void)
-                .map(|description_ptr| description_ptr.as_c_str().to_string_lossy().into_owned())
+            let message = MagickAutoRelinquish::new(message_ptr as *mut c_void)
+                .map(|message_ptr| message_ptr.as_c_str().to_string_lossy().into_owned())
                 .unwrap_or_else(|| "Unknown exception".to_string());
 
-            Exception::new(severity.into(), description).into()
+            Exception::from_message(severity.into(), &message)
+        }
+    }
+
+    /// Move the warning left on the wand by a successful call, if any, to
+    /// `self.warnings`.
+    unsafe fn collect_warning(&mut self) {
+        let exception = unsafe { self.get_exception() };
+        if exception.severity() == ExceptionSeverity::Warning {
+            // Otherwise the same warning would be collected again by the next
+            // successful call.
+            self.clear_exception();
+            self.warnings.push(exception);
         }
     }
 
+    /// Warnings raised by calls which succeeded anyway, e.g.
+    ///
+    /// `CorruptImageWarning` for a truncated JPEG, oldest first.
+    ///
+    /// Only the methods reading, pinging or writing images, and applying
+    ///
+    /// profiles, collect warnings. The warnings of the other methods, e.g. of a
+    ///
+    /// resize, are discarded.
+    ///
+    pub fn warnings(&self) -> &[Exception] {
+        &self.warnings
+    }
+
+    /// Take the collected warnings, see [MagickWand::warnings].
+    ///
+    /// Warnings accumulate until they are taken, so long-lived wands should
+    ///
+    /// call this regularly.
+    ///
+    pub fn take_warnings(&mut self) -> Vec<Exception> {
+        mem::take(&mut self.warnings)
+    }
+
     /// # Safety
     ///
     ///  * `wand` - must points to either NULL, or a valid allocation.
@@ -116,6 +200,7 @@ impl MagickWand<'_> {
     pub unsafe fn from_wand(wand: *mut graphicsmagick_sys::MagickWand) -> Option<Self> {
         NonNull::new(wand).map(|wand| MagickWand {
             wand,
+            warnings: Vec::new(),
             phantom: PhantomData,
         })
     }
@@ -139,11 +224,26 @@ impl Drop for MagickWand<'_> {
     }
 }
 
+// Safety:
+//
+// A `MagickWand` exclusively owns its image list, `ImageInfo` and
+// `ExceptionInfo`, none of which are tied to the thread that created them.
+// GraphicsMagick protects its global state (registries, resource counters,
+// pixel cache bookkeeping) with its own semaphores once `InitializeMagick`
+// has run, so moving the wand to another thread is sound. The borrowed
+// blob behind `'a` is a `&[u8]`, which is `Send` itself.
+//
+// It is not `Sync`: every binding goes through a `*mut` wand and may update
+// the wand's exception or iterator, so shared access from several threads
+// would race.
+unsafe impl Send for MagickWand<'_> {}
+
 impl Clone for MagickWand<'_> {
     fn clone(&self) -> Self {
         MagickWand {
             wand: NonNull::new(unsafe { CloneMagickWand(self.wand.as_ptr()) })
                 .expect("CloneMagickWand returns NULL"),
+            warnings: self.warnings.clone(),
             phantom: PhantomData,
         }
     }
@@ -297,7 +397,11 @@ impl MagickWand<'_> {
     ///
     /// threshold unchanged.
     ///
-    pub fn black_threshold_image(&mut self, threshold: &PixelWand) -> crate::Result<&mut Self> {
+    pub fn black_threshold_image(
+        &mut self,
+        threshold: impl AsPixelWand,
+    ) -> crate::Result<&mut Self> {
+        let threshold = threshold.as_pixel_wand();
         let status = unsafe { MagickBlackThresholdImage(self.wand.as_ptr(), threshold.wand()) };
         self.check_status(status)
     }
@@ -325,10 +429,11 @@ impl MagickWand<'_> {
     ///
     pub fn border_image(
         &mut self,
-        border_color: &PixelWand,
+        border_color: impl AsPixelWand,
         width: c_ulong,
         height: c_ulong,
     ) -> crate::Result<&mut Self> {
+        let border_color = border_color.as_pixel_wand();
         let status =
             unsafe { MagickBorderImage(self.wand.as_ptr(), border_color.wand(), width, height) };
         self.check_status(status)
@@ -401,14 +506,20 @@ impl MagickWand<'_> {
         self.check_status(status)
     }
 
-    // No usage.
-    //    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magickclearexception>
-    //    ///
-    //    /// MagickClearException() clears the last wand exception.
-    //    ///
-    //    pub fn clear_exception(&mut self) {
-    //        let status = unsafe { MagickClearException(self.wand.as_ptr()) };
-    //    }
+    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magickclearexception>
+    ///
+    /// MagickClearException() clears the last wand exception.
+    ///
+    /// Methods returning [crate::Result] already clear the exception after
+    ///
+    /// extracting it, so this is only needed after calling the raw API through
+    ///
+    /// [MagickWand::wand_mut].
+    ///
+    pub fn clear_exception(&mut self) -> &mut Self {
+        unsafe { MagickClearException(self.wand.as_ptr()) };
+        self
+    }
 
     /// <http://www.graphicsmagick.org/wand/magick_wand.html#magickclipimage>
     ///
@@ -474,12 +585,14 @@ impl MagickWand<'_> {
     ///
     pub fn color_floodfill_image(
         &mut self,
-        fill: &PixelWand,
+        fill: impl AsPixelWand,
         fuzz: c_double,
-        border_color: &PixelWand,
+        border_color: impl AsPixelWand,
         x: c_long,
         y: c_long,
     ) -> crate::Result<&mut Self> {
+        let fill = fill.as_pixel_wand();
+        let border_color = border_color.as_pixel_wand();
         let status = unsafe {
             MagickColorFloodfillImage(
                 self.wand.as_ptr(),
@@ -499,9 +612,11 @@ impl MagickWand<'_> {
     ///
     pub fn colorize_image(
         &mut self,
-        colorize: &PixelWand,
-        opacity: &PixelWand,
+        colorize: impl AsPixelWand,
+        opacity: impl AsPixelWand,
     ) -> crate::Result<&mut Self> {
+        let colorize = colorize.as_pixel_wand();
+        let opacity = opacity.as_pixel_wand();
         let status =
             unsafe { MagickColorizeImage(self.wand.as_ptr(), colorize.wand(), opacity.wand()) };
         self.check_status(status)
@@ -838,12 +953,13 @@ impl MagickWand<'_> {
     ///
     pub fn frame_image(
         &mut self,
-        matte_color: &PixelWand,
+        matte_color: impl AsPixelWand,
         width: c_ulong,
         height: c_ulong,
         inner_bevel: c_long,
         outer_bevel: c_long,
     ) -> crate::Result<&mut Self> {
+        let matte_color = matte_color.as_pixel_wand();
         let status = unsafe {
             MagickFrameImage(
                 self.wand.as_ptr(),
@@ -1566,6 +1682,63 @@ impl<'a> MagickWand<'a> {
         }
     }
 
+    /// Like [`MagickWand::get_image_profile`], but returns the whole profile
+    ///
+    /// as bytes, since binary profiles (ICC, EXIF, ...) may contain NUL bytes.
+    ///
+    /// # Return
+    ///
+    /// `None` if the image has no profile named `name`.
+    ///
+    pub fn get_image_profile_bytes<'s>(
+        &mut self,
+        name: impl IntoNullTerminatedString<'s>,
+    ) -> Option<MagickBoxSlice<u8>> {
+        let mut length = 0;
+        let name = name.into_null_terminated_string();
+        let profile = unsafe {
+            let profile = MagickGetImageProfile(self.wand.as_ptr(), name.as_ptr(), &mut length);
+            MagickBoxSlice::new(profile, length.try_into().unwrap())
+        };
+        if profile.is_none() {
+            // Don't leave the `WandContainsNoImages` exception of an empty
+            // wand to the next failed call.
+            self.clear_exception();
+        }
+        profile
+    }
+
+    /// Names of the profiles of the current image, such as `"ICM"`, `"IPTC"`
+    ///
+    /// or `"EXIF"`, in no particular order, empty if the wand has no image.
+    ///
+    pub fn get_image_profile_names(&mut self) -> Vec<String> {
+        let mut names = Vec::new();
+        unsafe {
+            let image = GetImageFromMagickWand(self.wand.as_ptr());
+            if image.is_null() {
+                // Don't leave the `WandContainsNoImages` exception to the next
+                // failed call.
+                self.clear_exception();
+                return names;
+            }
+            let iterator = AllocateImageProfileIterator(image);
+            if iterator.is_null() {
+                return names;
+            }
+            let mut name = std::ptr::null();
+            let mut profile = std::ptr::null();
+            let mut length = 0;
+            while NextImageProfile(iterator, &mut name, &mut profile, &mut length) != MagickFail {
+                if !name.is_null() {
+                    names.push(CStr::from_ptr(name).to_string_lossy().into_owned());
+                }
+            }
+            DeallocateImageProfileIterator(iterator);
+        }
+        names
+    }
+
     /// <http://www.graphicsmagick.org/wand/magick_wand.html#magickgetimageredprimary>
     ///
     /// MagickGetImageRedPrimary() returns the chromaticy red primary point.
@@ -2075,10 +2248,11 @@ impl<'a> MagickWand<'a> {
         &mut self,
         opacity: Quantum,
         fuzz: c_double,
-        border_color: &PixelWand,
+        border_color: impl AsPixelWand,
         x: c_long,
         y: c_long,
     ) -> crate::Result<&mut Self> {
+        let border_color = border_color.as_pixel_wand();
         let status = unsafe {
             MagickMatteFloodfillImage(self.wand.as_ptr(), opacity, fuzz, border_color.wand(), x, y)
         };
@@ -2134,6 +2308,8 @@ impl<'a> MagickWand<'a> {
     ///
     /// of the image optionally appearing just below the individual tile.
     ///
+    /// The geometries may be given as [Geometry](crate::geometry::Geometry).
+    ///
     pub fn montage_image<'s>(
         &mut self,
         drawing_wand: &DrawingWand,
@@ -2294,10 +2470,12 @@ impl<'a> MagickWand<'a> {
     ///
     pub fn opaque_image(
         &mut self,
-        target: &PixelWand,
-        fill: &PixelWand,
+        target: impl AsPixelWand,
+        fill: impl AsPixelWand,
         fuzz: c_double,
     ) -> crate::Result<&mut Self> {
+        let target = target.as_pixel_wand();
+        let fill = fill.as_pixel_wand();
         let status =
             unsafe { MagickOpaqueImage(self.wand.as_ptr(), target.wand(), fill.wand(), fuzz) };
         self.check_status(status)
@@ -2319,7 +2497,7 @@ impl<'a> MagickWand<'a> {
     ) -> crate::Result<&mut Self> {
         let filename = filename.into_null_terminated_string();
         let status = unsafe { MagickPingImage(self.wand.as_ptr(), filename.as_ptr()) };
-        self.check_status(status)
+        self.check_coder_status(status)
     }
 
     /// <http://www.graphicsmagick.org/wand/magick_wand.html#magickpreviewimages>
@@ -2374,7 +2552,28 @@ impl<'a> MagickWand<'a> {
                 length,
             )
         };
-        self.check_status(status)
+        self.check_coder_status(status)
+    }
+
+    /// Like [`MagickWand::profile_image`], but takes the profile as bytes,
+    ///
+    /// since binary profiles (ICC, EXIF, ...) may contain NUL bytes.
+    ///
+    pub fn profile_image_bytes<'s>(
+        &mut self,
+        name: impl IntoNullTerminatedString<'s>,
+        profile: &[u8],
+    ) -> crate::Result<&mut Self> {
+        let name = name.into_null_terminated_string();
+        let status = unsafe {
+            MagickProfileImage(
+                self.wand.as_ptr(),
+                name.as_ptr(),
+                profile.as_ptr(),
+                profile.len() as size_t,
+            )
+        };
+        self.check_coder_status(status)
     }
 
     /// <http://www.graphicsmagick.org/wand/magick_wand.html#magickquantizeimage>
@@ -2576,7 +2775,7 @@ impl<'a> MagickWand<'a> {
     ) -> crate::Result<&mut Self> {
         let filename = filename.into_null_terminated_string();
         let status = unsafe { MagickReadImage(self.wand.as_ptr(), filename.as_ptr()) };
-        self.check_status(status)
+        self.check_coder_status(status)
     }
 
     /// <http://www.graphicsmagick.org/wand/magick_wand.html#magickreadimageblob>
@@ -2584,22 +2783,74 @@ impl<'a> MagickWand<'a> {
     /// MagickReadImageBlob() reads an image or image sequence from a blob.
     ///
     pub fn read_image_blob(&mut self, blob: &'a [u8]) -> crate::Result<&mut Self> {
+        self.read_image_transient_blob(blob)
+    }
+
+    /// Like [MagickWand::read_image_blob], for a `blob` which may be dropped
+    ///
+    /// as soon as this call returns.
+    ///
+    /// `MagickReadImageBlob` decodes the blob into images owned by the wand,
+    ///
+    /// and keeps no reference to it afterwards, so the `'a` borrow of
+    ///
+    /// [MagickWand::read_image_blob] is stricter than needed, and only kept
+    ///
+    /// for compatibility.
+    ///
+    pub(crate) fn read_image_transient_blob(&mut self, blob: &[u8]) -> crate::Result<&mut Self> {
         let length = blob.len() as size_t;
         let blob = blob.as_ptr();
         let status = unsafe { MagickReadImageBlob(self.wand.as_ptr(), blob, length) };
-        self.check_status(status)
+        self.check_coder_status(status)
     }
 
-    // Not Need
-    //    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magickreadimagefile>
-    //    ///
-    //    /// MagickReadImageFile() reads an image or image sequence from an open file
-    //    ///
-    //    /// descriptor.
-    //    ///
-    //    pub fn read_image_file(&mut self, file: &mut File) -> crate::Result<&mut Self> {
-    //        // let status = unsafe { MagickReadImageFile(self.wand.as_ptr(), file) };
-    //    }
+    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magickreadimagefile>
+    ///
+    /// MagickReadImageFile() reads an image or image sequence from an open file
+    ///
+    /// descriptor.
+    ///
+    /// The descriptor is the read end of a pipe, which a scoped thread fills
+    ///
+    /// from `reader`, so the encoded input is never buffered as a whole. Coders
+    ///
+    /// which need to seek are fed through a temporary file by GraphicsMagick.
+    ///
+    /// `reader` is read until EOF, even past the end of the image.
+    ///
+    /// Unlike [MagickWand::read_image_blob], the wand doesn't borrow anything
+    ///
+    /// once this call returns.
+    ///
+    #[cfg(unix)]
+    pub fn read_image_from<R: Read + Send>(&mut self, mut reader: R) -> crate::Result<&mut Self> {
+        let (file, mut input) = crate::utils::read_pipe()?;
+        let wand = self.wand.as_ptr();
+        let (status, drained, copied) = thread::scope(|scope| {
+            let copier = scope.spawn(move || io::copy(&mut reader, &mut input));
+            let status = unsafe { MagickReadImageFile(wand, file.as_ptr()) };
+            // Keep the read end open until the copier is done, if the coder
+            // stopped before EOF, or its next write would raise SIGPIPE.
+            let drained = file.drain();
+            drop(file);
+            (status, drained, copier.join())
+        });
+        copied.unwrap_or_else(|payload| panic::resume_unwind(payload))?;
+        drained?;
+        self.check_coder_status(status)
+    }
+
+    /// Buffered fallback of [MagickWand::read_image_blob] for targets without
+    ///
+    /// pipes: `reader` is read until EOF into memory, then decoded.
+    ///
+    #[cfg(not(unix))]
+    pub fn read_image_from<R: Read + Send>(&mut self, mut reader: R) -> crate::Result<&mut Self> {
+        let mut blob = Vec::new();
+        reader.read_to_end(&mut blob)?;
+        self.read_image_transient_blob(&blob)
+    }
 
     /// <http://www.graphicsmagick.org/wand/magick_wand.html#magickreducenoiseimage>
     ///
@@ -2766,9 +3017,10 @@ impl<'a> MagickWand<'a> {
     ///
     pub fn rotate_image(
         &mut self,
-        background: &PixelWand,
+        background: impl AsPixelWand,
         degrees: c_double,
     ) -> crate::Result<&mut Self> {
+        let background = background.as_pixel_wand();
         let status = unsafe { MagickRotateImage(self.wand.as_ptr(), background.wand(), degrees) };
         self.check_status(status)
     }
@@ -3009,8 +3261,9 @@ impl<'a> MagickWand<'a> {
     ///
     pub fn set_image_background_color(
         &mut self,
-        background: &PixelWand,
+        background: impl AsPixelWand,
     ) -> crate::Result<&mut Self> {
+        let background = background.as_pixel_wand();
         let status =
             unsafe { MagickSetImageBackgroundColor(self.wand.as_ptr(), background.wand()) };
         self.check_status(status)
@@ -3029,7 +3282,8 @@ impl<'a> MagickWand<'a> {
     ///
     /// MagickSetImageBorderColor() sets the image border color.
     ///
-    pub fn set_image_border_color(&mut self, border: &PixelWand) -> crate::Result<&mut Self> {
+    pub fn set_image_border_color(&mut self, border: impl AsPixelWand) -> crate::Result<&mut Self> {
+        let border = border.as_pixel_wand();
         let status = unsafe { MagickSetImageBorderColor(self.wand.as_ptr(), border.wand()) };
         self.check_status(status)
     }
@@ -3043,8 +3297,9 @@ impl<'a> MagickWand<'a> {
     pub fn set_image_colormap_color(
         &mut self,
         index: c_ulong,
-        color: &PixelWand,
+        color: impl AsPixelWand,
     ) -> crate::Result<&mut Self> {
+        let color = color.as_pixel_wand();
         let status =
             unsafe { MagickSetImageColormapColor(self.wand.as_ptr(), index, color.wand()) };
         self.check_status(status)
@@ -3271,7 +3526,8 @@ impl<'a> MagickWand<'a> {
     ///
     /// MagickSetImageMatteColor() sets the image matte color.
     ///
-    pub fn set_image_matte_color(&mut self, matte: &PixelWand) -> crate::Result<&mut Self> {
+    pub fn set_image_matte_color(&mut self, matte: impl AsPixelWand) -> crate::Result<&mut Self> {
+        let matte = matte.as_pixel_wand();
         let status = unsafe { MagickSetImageMatteColor(self.wand.as_ptr(), matte.wand()) };
         self.check_status(status)
     }
@@ -3452,174 +3708,886 @@ impl MagickWand<'_> {
     }
 }
 
-impl MagickWand<'_> {
-    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetimageprofile>
-    ///
-    /// MagickSetImageProfile() adds a named profile to the magick wand.  If a
-    ///
-    /// profile with the same name already exists, it is replaced.  This method
-    ///
-    /// differs from the MagickProfileImage() method in that it does not apply any
-    ///
-    /// CMS color profiles.
-    ///
-    pub fn set_image_profile<'s>(
-        &mut self,
-        name: impl IntoNullTerminatedString<'s>,
-        profile: impl IntoNullTerminatedString<'s>,
-        length: c_ulong,
-    ) -> crate::Result<&mut Self> {
-        let name = name.into_null_terminated_string();
-        let profile = profile.into_null_terminated_string();
-        let status = unsafe {
-            MagickSetImageProfile(
-                self.wand.as_ptr(),
-                name.as_ptr(),
-                profile.as_ptr().cast(),
-                length,
-            )
-        };
-        self.check_status(status)
+/// Mutable view of a rectangular region of the current image, borrowed from
+/// its pixel cache, created by [`MagickWand::pixel_region`].
+///
+/// The rows are slices of the cache itself, got with `GetImagePixelsEx`, so
+/// nothing is copied whatever the size of the region. Once the pixels are
+/// borrowed mutably, the image is switched to `DirectClass`, so that its
+/// colormap no longer overrides them, and the changes are synchronized back to
+/// the image with `SyncImagePixelsEx` by [`PixelRegion::commit`] or on drop.
+/// Depending on where the cache lives, the changes may be visible in the image
+/// before that. A region which is only read leaves the image untouched, e.g. a
+/// palette image keeps its colormap.
+///
+/// The [PixelPacket]s hold the channels of the image colorspace as stored,
+/// e.g. cyan, magenta, yellow and black for a CMYK image.
+///
+/// Errors of the synchronization on drop are ignored, call
+/// [`PixelRegion::commit`] to handle them.
+#[derive(Debug)]
+pub struct PixelRegion<'w, 'a> {
+    wand: PhantomData<&'w mut MagickWand<'a>>,
+    image: NonNull<Image>,
+    pixels: NonNull<PixelPacket>,
+    columns: c_ulong,
+    rows: c_ulong,
+    dirty: bool,
+}
+
+impl PixelRegion<'_, '_> {
+    /// Number of columns of the region.
+    pub fn columns(&self) -> c_ulong {
+        self.columns
     }
 
-    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetimageredprimary>
-    ///
-    /// MagickSetImageRedPrimary() sets the image chromaticity red primary point.
-    ///
-    pub fn set_image_red_primary(&mut self, x: c_double, y: c_double) -> crate::Result<&mut Self> {
-        let status = unsafe { MagickSetImageRedPrimary(self.wand.as_ptr(), x, y) };
-        self.check_status(status)
+    /// Number of rows of the region.
+    pub fn rows(&self) -> c_ulong {
+        self.rows
     }
 
-    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetimagerenderingintent>
-    ///
-    /// MagickSetImageRenderingIntent() sets the image rendering intent.
-    ///
-    pub fn set_image_rendering_intent(
-        &mut self,
-        rendering_intent: RenderingIntent,
-    ) -> crate::Result<&mut Self> {
-        let status =
-            unsafe { MagickSetImageRenderingIntent(self.wand.as_ptr(), rendering_intent.into()) };
-        self.check_status(status)
+    /// All pixels of the region, row by row.
+    pub fn pixels(&self) -> &[PixelPacket] {
+        // Safety:
+        //
+        // The cache holds `columns * rows` pixels at `pixels` until the next
+        // access to the pixels of the image, which the borrow of the wand
+        // prevents.
+        unsafe { std::slice::from_raw_parts(self.pixels.as_ptr(), self.len()) }
+    }
+
+    /// All pixels of the region, row by row.
+    pub fn pixels_mut(&mut self) -> &mut [PixelPacket] {
+        if !self.dirty {
+            // The pixels are about to be changed regardless of the colormap.
+            unsafe { (*self.image.as_ptr()).storage_class = ClassType_DirectClass };
+            self.dirty = true;
+        }
+        // Safety: see `pixels`.
+        unsafe { std::slice::from_raw_parts_mut(self.pixels.as_ptr(), self.len()) }
     }
 
-    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetimageresolution>
+    /// Row `y` of the region, relative to its top.
     ///
-    /// MagickSetImageResolution() sets the image resolution.
+    /// # Panics
     ///
-    pub fn set_image_resolution(
-        &mut self,
-        x_resolution: c_double,
-        y_resolution: c_double,
-    ) -> crate::Result<&mut Self> {
-        let status =
-            unsafe { MagickSetImageResolution(self.wand.as_ptr(), x_resolution, y_resolution) };
-        self.check_status(status)
+    /// If `y` is out of the region.
+    pub fn row(&self, y: c_ulong) -> &[PixelPacket] {
+        let range = self.row_range(y);
+        &self.pixels()[range]
     }
 
-    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetimagescene>
+    /// Row `y` of the region, relative to its top.
     ///
-    /// MagickSetImageScene() sets the image scene.
+    /// # Panics
     ///
-    pub fn set_image_scene(&mut self, scene: c_ulong) -> crate::Result<&mut Self> {
-        let status = unsafe { MagickSetImageScene(self.wand.as_ptr(), scene) };
-        self.check_status(status)
+    /// If `y` is out of the region.
+    pub fn row_mut(&mut self, y: c_ulong) -> &mut [PixelPacket] {
+        let range = self.row_range(y);
+        &mut self.pixels_mut()[range]
     }
 
-    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetimagetype>
-    ///
-    /// MagickSetImageType() sets the image type.
-    ///
-    pub fn set_image_type(&mut self, image_type: ImageType) -> crate::Result<&mut Self> {
-        let status = unsafe { MagickSetImageType(self.wand.as_ptr(), image_type.into()) };
-        self.check_status(status)
+    /// Iterate over the rows of the region, from top to bottom.
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[PixelPacket]> {
+        self.pixels().chunks_exact(self.row_len())
     }
 
-    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetimagesavedtype>
-    ///
-    /// MagickSetImageSavedType() sets the image type that will be used when the
-    ///
-    /// image is saved.
-    ///
-    pub fn set_image_saved_type(&mut self, image_type: ImageType) -> crate::Result<&mut Self> {
-        let status = unsafe { MagickSetImageSavedType(self.wand.as_ptr(), image_type.into()) };
-        self.check_status(status)
+    /// Iterate over the rows of the region, from top to bottom.
+    pub fn iter_rows_mut(&mut self) -> impl Iterator<Item = &mut [PixelPacket]> {
+        let row_len = self.row_len();
+        self.pixels_mut().chunks_exact_mut(row_len)
     }
 
-    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetimageunits>
-    ///
-    /// MagickSetImageUnits() sets the image units of resolution.
-    ///
-    pub fn set_image_units(&mut self, units: ResolutionType) -> crate::Result<&mut Self> {
-        let status = unsafe { MagickSetImageUnits(self.wand.as_ptr(), units.into()) };
-        self.check_status(status)
+    /// Synchronize the changes of the region to the image, if any.
+    pub fn commit(mut self) -> crate::Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        self.dirty = false;
+        self.sync()
     }
 
-    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetimagevirtualpixelmethod>
-    ///
-    /// MagickSetImageVirtualPixelMethod() sets the image virtual pixel method.
-    ///
-    pub fn set_image_virtual_pixel_method(
-        &mut self,
-        method: VirtualPixelMethod,
-    ) -> crate::Result<&mut Self> {
-        let status = unsafe { MagickSetImageVirtualPixelMethod(self.wand.as_ptr(), method.into()) };
-        self.check_status(status)
+    fn len(&self) -> usize {
+        self.columns as usize * self.rows as usize
     }
 
-    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetinterlacescheme>
-    ///
-    /// MagickSetInterlaceScheme() sets the interlace scheme used when writing
-    ///
-    /// the image.
-    ///
-    pub fn set_interlace_scheme(
-        &mut self,
-        interlace_scheme: InterlaceType,
-    ) -> crate::Result<&mut Self> {
-        let status =
-            unsafe { MagickSetInterlaceScheme(self.wand.as_ptr(), interlace_scheme.into()) };
-        self.check_status(status)
+    fn row_len(&self) -> usize {
+        // `chunks_exact` panics on zero, and an empty region has no rows anyway.
+        (self.columns as usize).max(1)
     }
 
-    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetresolution>
-    ///
-    /// MagickSetResolution() sets the resolution (density) of the magick wand.
-    ///
-    /// Set it before you read an EPS, PDF, or Postscript file in order to
-    ///
-    /// influence the size of the returned image, or after an image has already
-    ///
-    /// been created to influence the rendered image size when used with
-    ///
-    /// typesetting software.
-    ///
-    /// Also see MagickSetResolutionUnits() which specifies the units to use for
-    ///
-    /// the image resolution.
-    ///
-    pub fn set_resolution(
-        &mut self,
-        x_resolution: c_double,
-        y_resolution: c_double,
-    ) -> crate::Result<&mut Self> {
-        let status = unsafe { MagickSetResolution(self.wand.as_ptr(), x_resolution, y_resolution) };
-        self.check_status(status)
+    fn row_range(&self, y: c_ulong) -> Range<usize> {
+        assert!(
+            y < self.rows,
+            "row {} out of region of {} rows",
+            y,
+            self.rows
+        );
+        let start = y as usize * self.columns as usize;
+        start..start + self.columns as usize
     }
 
-    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetresolutionunits>
-    ///
-    /// MagickSetResolutionUnits() sets the resolution units of the magick wand.
-    ///
-    /// It should be used in conjunction with MagickSetResolution().
-    ///
-    /// This method works both before and after an image has been read.
-    ///
-    /// Also see MagickSetImageUnits() which specifies the units which apply to
-    ///
-    /// the image resolution setting after an image has been read.
-    ///
+    fn sync(&mut self) -> crate::Result<()> {
+        with_exception_info(|exception| unsafe {
+            if SyncImagePixelsEx(self.image.as_ptr(), exception) == MagickFail {
+                Err(Exception::from_exception_info(exception).into())
+            } else {
+                Ok(())
+            }
+        })
+    }
+}
+
+impl Drop for PixelRegion<'_, '_> {
+    fn drop(&mut self) {
+        if self.dirty {
+            let _ = self.sync();
+        }
+    }
+}
+
+/// Read row `y` into `buffer`, replacing its content.
+fn read_row_into<T: MagickWandExportType>(
+    wand: &mut MagickWand<'_>,
+    map: &NullTerminatedStr,
+    columns: c_ulong,
+    y: c_ulong,
+    buffer: &mut Vec<T>,
+) -> crate::Result<()> {
+    buffer.clear();
+    let input = MagickWandExportSlice::new(columns, 1, map, buffer.spare_capacity_mut())
+        .expect("buffer is allocated for a whole row");
+    let len = input.len();
+    wand.write_image_pixels_to(0, y as c_long, input)?;
+
+    // Safety:
+    //
+    // MagickGetImagePixels succeeds, so it should have written
+    // `len` elements into the vec.
+    unsafe {
+        buffer.set_len(len);
+    }
+    Ok(())
+}
+
+/// Scanlines of the current image, created by [`MagickWand::rows`].
+///
+/// Every row is read into the same buffer, so this isn't an [Iterator]: the
+/// row returned by [`Rows::next_row`] has to be dropped before the next one
+/// is read.
+///
+/// ```
+/// use graphicsmagick::{initialize, wand::MagickWand};
+///
+/// initialize();
+///
+/// let mut mw = MagickWand::new();
+/// mw.read_image("xc:white").unwrap();
+///
+/// let mut rows = mw.rows::<u8>("I");
+/// while let Some(row) = rows.next_row() {
+///     assert!(row.unwrap().iter().all(|&intensity| intensity == 255));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Rows<'w, 'a, 's, T> {
+    wand: &'w mut MagickWand<'a>,
+    map: Cow<'s, NullTerminatedStr>,
+    columns: c_ulong,
+    rows: c_ulong,
+    y: c_ulong,
+    buffer: Vec<T>,
+}
+
+impl<T: MagickWandExportType> Rows<'_, '_, '_, T> {
+    /// Number of columns, i.e. `row.len() / map.len()`.
+    pub fn columns(&self) -> c_ulong {
+        self.columns
+    }
+
+    /// Number of rows of the image.
+    pub fn rows(&self) -> c_ulong {
+        self.rows
+    }
+
+    /// Index of the row that the next call of [`Rows::next_row`] reads.
+    pub fn y(&self) -> c_ulong {
+        self.y
+    }
+
+    /// Read the next row, `None` after the last one.
+    pub fn next_row(&mut self) -> Option<crate::Result<&[T]>> {
+        if self.y >= self.rows {
+            return None;
+        }
+        let y = self.y;
+        self.y += 1;
+        Some(
+            read_row_into(self.wand, &self.map, self.columns, y, &mut self.buffer)
+                .map(|()| &*self.buffer),
+        )
+    }
+}
+
+/// Mutable scanlines of the current image, created by
+/// [`MagickWand::rows_mut`].
+///
+/// Like [Rows], but a row returned by [`RowsMut::next_row`] is written back
+/// with `MagickSetImagePixels` when the next row is requested, by
+/// [`RowsMut::finish`], or on drop. Errors of the write back on drop are
+/// ignored.
+///
+/// ```
+/// use graphicsmagick::{initialize, wand::MagickWand};
+///
+/// initialize();
+///
+/// let mut mw = MagickWand::new();
+/// mw.read_image("xc:white").unwrap();
+///
+/// let mut rows = mw.rows_mut::<u8>("RGB");
+/// while let Some(row) = rows.next_row() {
+///     for rgb in row.unwrap().chunks_exact_mut(3) {
+///         rgb[1] = 0;
+///     }
+/// }
+/// rows.finish().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct RowsMut<'w, 'a, 's, T: MagickWandExportType> {
+    wand: &'w mut MagickWand<'a>,
+    map: Cow<'s, NullTerminatedStr>,
+    columns: c_ulong,
+    rows: c_ulong,
+    y: c_ulong,
+    buffer: Vec<T>,
+    /// Row held in `buffer`, which has to be written back.
+    pending: Option<c_ulong>,
+}
+
+impl<T: MagickWandExportType> RowsMut<'_, '_, '_, T> {
+    /// Number of columns, i.e. `row.len() / map.len()`.
+    pub fn columns(&self) -> c_ulong {
+        self.columns
+    }
+
+    /// Number of rows of the image.
+    pub fn rows(&self) -> c_ulong {
+        self.rows
+    }
+
+    /// Index of the row that the next call of [`RowsMut::next_row`] reads.
+    pub fn y(&self) -> c_ulong {
+        self.y
+    }
+
+    /// Write back the previous row, then read the next row, `None` after the
+    /// last one.
+    pub fn next_row(&mut self) -> Option<crate::Result<&mut [T]>> {
+        if let Err(e) = self.write_back() {
+            return Some(Err(e));
+        }
+        if self.y >= self.rows {
+            return None;
+        }
+        let y = self.y;
+        self.y += 1;
+        if let Err(e) = read_row_into(self.wand, &self.map, self.columns, y, &mut self.buffer) {
+            return Some(Err(e));
+        }
+        self.pending = Some(y);
+        Some(Ok(&mut self.buffer))
+    }
+
+    /// Write back the last row returned by [`RowsMut::next_row`].
+    pub fn finish(mut self) -> crate::Result<()> {
+        self.write_back()
+    }
+
+    fn write_back(&mut self) -> crate::Result<()> {
+        if let Some(y) = self.pending.take() {
+            let import = MagickWandImportSlice::new(self.columns, 1, &*self.map, &self.buffer)
+                .expect("buffer holds a whole row");
+            self.wand.set_image_pixels(0, y as c_long, import)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: MagickWandExportType> Drop for RowsMut<'_, '_, '_, T> {
+    fn drop(&mut self) {
+        let _ = self.write_back();
+    }
+}
+
+/// Cursor shared by [Frames] and [FramesMut], which moves the current image
+/// of the wand and moves it back on drop.
+#[derive(Debug)]
+struct FrameCursor<'w, 'a> {
+    wand: &'w mut MagickWand<'a>,
+    /// Index of the current image before the iteration started.
+    saved_index: c_long,
+    next: c_ulong,
+    count: c_ulong,
+}
+
+impl<'w, 'a> FrameCursor<'w, 'a> {
+    fn new(wand: &'w mut MagickWand<'a>) -> Self {
+        let count = wand.get_number_images();
+        let saved_index = wand.get_image_index() as c_long;
+        FrameCursor {
+            wand,
+            saved_index,
+            next: 0,
+            count,
+        }
+    }
+
+    /// Make the next image current, returning its index.
+    fn advance(&mut self) -> Option<c_ulong> {
+        if self.next >= self.count {
+            return None;
+        }
+        let index = self.next;
+        self.next += 1;
+        self.wand
+            .set_image_index(index as c_long)
+            .expect("index is below the number of images");
+        Some(index)
+    }
+}
+
+impl Drop for FrameCursor<'_, '_> {
+    fn drop(&mut self) {
+        if self.count > 0 {
+            let _ = self.wand.set_image_index(self.saved_index);
+        }
+    }
+}
+
+/// Image of a sequence, yielded by [`Frames::next_frame`].
+///
+/// Every getter reads the image at [`Frame::index`].
+#[derive(Debug)]
+pub struct Frame<'f, 'a> {
+    wand: &'f mut MagickWand<'a>,
+    index: c_ulong,
+}
+
+impl Frame<'_, '_> {
+    /// Index of the image in the sequence.
+    pub fn index(&self) -> c_ulong {
+        self.index
+    }
+
+    /// See [`MagickWand::get_image_width`].
+    pub fn width(&mut self) -> c_ulong {
+        self.wand.get_image_width()
+    }
+
+    /// See [`MagickWand::get_image_height`].
+    pub fn height(&mut self) -> c_ulong {
+        self.wand.get_image_height()
+    }
+
+    /// See [`MagickWand::get_image_delay`].
+    pub fn delay(&mut self) -> c_ulong {
+        self.wand.get_image_delay()
+    }
+
+    /// See [`MagickWand::get_image_dispose`].
+    pub fn dispose(&mut self) -> DisposeType {
+        self.wand.get_image_dispose()
+    }
+
+    /// See [`MagickWand::get_image_page`].
+    ///
+    /// # Return
+    ///
+    /// (width, height, x, y)
+    pub fn page(&mut self) -> crate::Result<(c_ulong, c_ulong, c_long, c_long)> {
+        self.wand.get_image_page()
+    }
+
+    /// See [`MagickWand::get_image_scene`].
+    pub fn scene(&mut self) -> c_ulong {
+        self.wand.get_image_scene()
+    }
+
+    /// See [`MagickWand::get_image_iterations`].
+    #[cfg(feature = "v1_3_26")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "v1_3_26")))]
+    pub fn iterations(&mut self) -> c_ulong {
+        self.wand.get_image_iterations()
+    }
+}
+
+/// Mutable image of a sequence, yielded by [`FramesMut::next_frame`].
+///
+/// Dereferences to [Frame] for the getters.
+#[derive(Debug)]
+pub struct FrameMut<'f, 'a> {
+    frame: Frame<'f, 'a>,
+}
+
+impl FrameMut<'_, '_> {
+    /// See [`MagickWand::set_image_delay`].
+    pub fn set_delay(&mut self, delay: c_ulong) -> crate::Result<&mut Self> {
+        self.frame.wand.set_image_delay(delay)?;
+        Ok(self)
+    }
+
+    /// See [`MagickWand::set_image_dispose`].
+    pub fn set_dispose(&mut self, dispose: DisposeType) -> crate::Result<&mut Self> {
+        self.frame.wand.set_image_dispose(dispose)?;
+        Ok(self)
+    }
+
+    /// See [`MagickWand::set_image_page`].
+    pub fn set_page(
+        &mut self,
+        width: c_ulong,
+        height: c_ulong,
+        x: c_long,
+        y: c_long,
+    ) -> crate::Result<&mut Self> {
+        self.frame.wand.set_image_page(width, height, x, y)?;
+        Ok(self)
+    }
+
+    /// See [`MagickWand::set_image_scene`].
+    pub fn set_scene(&mut self, scene: c_ulong) -> crate::Result<&mut Self> {
+        self.frame.wand.set_image_scene(scene)?;
+        Ok(self)
+    }
+
+    /// See [`MagickWand::set_image_iterations`].
+    #[cfg(feature = "v1_3_26")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "v1_3_26")))]
+    pub fn set_iterations(&mut self, iterations: c_ulong) -> crate::Result<&mut Self> {
+        self.frame.wand.set_image_iterations(iterations)?;
+        Ok(self)
+    }
+}
+
+impl<'f, 'a> Deref for FrameMut<'f, 'a> {
+    type Target = Frame<'f, 'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.frame
+    }
+}
+
+impl DerefMut for FrameMut<'_, '_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.frame
+    }
+}
+
+/// Images of a sequence, created by [`MagickWand::frames`].
+///
+/// [`Frames::next_frame`] makes each image current in turn. The image that
+/// was current before is made current again on drop.
+///
+/// ```
+/// use graphicsmagick::{initialize, wand::MagickWand};
+///
+/// initialize();
+///
+/// let mut mw = MagickWand::new();
+/// mw.read_image("xc:white").unwrap();
+/// mw.read_image("xc:black").unwrap();
+///
+/// let mut frames = mw.frames();
+/// while let Some(mut frame) = frames.next_frame() {
+///     assert_eq!(frame.width(), 1);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Frames<'w, 'a> {
+    cursor: FrameCursor<'w, 'a>,
+}
+
+impl<'a> Frames<'_, 'a> {
+    /// Number of images in the sequence.
+    pub fn len(&self) -> c_ulong {
+        self.cursor.count
+    }
+
+    /// Whether the sequence has no images.
+    pub fn is_empty(&self) -> bool {
+        self.cursor.count == 0
+    }
+
+    /// Make the next image current, `None` after the last one.
+    pub fn next_frame(&mut self) -> Option<Frame<'_, 'a>> {
+        let index = self.cursor.advance()?;
+        Some(Frame {
+            wand: self.cursor.wand,
+            index,
+        })
+    }
+}
+
+/// Mutable images of a sequence, created by [`MagickWand::frames_mut`].
+///
+/// Like [Frames], but yields [FrameMut].
+///
+/// ```
+/// use graphicsmagick::{initialize, wand::MagickWand};
+///
+/// initialize();
+///
+/// let mut mw = MagickWand::new();
+/// mw.read_image("xc:white").unwrap();
+/// mw.read_image("xc:black").unwrap();
+///
+/// let mut frames = mw.frames_mut();
+/// while let Some(mut frame) = frames.next_frame() {
+///     frame.set_delay(10).unwrap();
+/// }
+/// ```
+#[derive(Debug)]
+pub struct FramesMut<'w, 'a> {
+    cursor: FrameCursor<'w, 'a>,
+}
+
+impl<'a> FramesMut<'_, 'a> {
+    /// Number of images in the sequence.
+    pub fn len(&self) -> c_ulong {
+        self.cursor.count
+    }
+
+    /// Whether the sequence has no images.
+    pub fn is_empty(&self) -> bool {
+        self.cursor.count == 0
+    }
+
+    /// Make the next image current, `None` after the last one.
+    pub fn next_frame(&mut self) -> Option<FrameMut<'_, 'a>> {
+        let index = self.cursor.advance()?;
+        Some(FrameMut {
+            frame: Frame {
+                wand: self.cursor.wand,
+                index,
+            },
+        })
+    }
+}
+
+impl<'a> MagickWand<'a> {
+    /// Borrow a region of the current image as rows of [PixelPacket], see
+    /// [PixelRegion].
+    ///
+    /// ```
+    /// use graphicsmagick::{initialize, wand::MagickWand};
+    ///
+    /// initialize();
+    ///
+    /// let mut mw = MagickWand::new();
+    /// mw.read_image("xc:white").unwrap();
+    ///
+    /// let mut region = mw.pixel_region(0, 0, 1, 1).unwrap();
+    /// for pixel in region.pixels_mut() {
+    ///     pixel.green = 0;
+    /// }
+    /// region.commit().unwrap();
+    /// ```
+    pub fn pixel_region(
+        &mut self,
+        x_offset: c_long,
+        y_offset: c_long,
+        columns: c_ulong,
+        rows: c_ulong,
+    ) -> crate::Result<PixelRegion<'_, 'a>> {
+        let Some(image) = NonNull::new(unsafe { GetImageFromMagickWand(self.wand.as_ptr()) })
+        else {
+            return Err(unsafe { self.get_error() });
+        };
+        let (image_columns, image_rows) = unsafe { (image.as_ref().columns, image.as_ref().rows) };
+        let inside = |offset: c_long, length: c_ulong, image_length: c_ulong| {
+            c_ulong::try_from(offset)
+                .ok()
+                .and_then(|offset| offset.checked_add(length))
+                .is_some_and(|end| end <= image_length)
+        };
+        if !inside(x_offset, columns, image_columns) || !inside(y_offset, rows, image_rows) {
+            return Err(Exception::new(
+                error::ExceptionType::OptionError,
+                "pixel region is out of the image".to_owned(),
+                Some(format!(
+                    "{}x{}+{}+{} of a {}x{} image",
+                    columns, rows, x_offset, y_offset, image_columns, image_rows
+                )),
+            )
+            .into());
+        }
+
+        let pixels = with_exception_info(|exception| unsafe {
+            let pixels =
+                GetImagePixelsEx(image.as_ptr(), x_offset, y_offset, columns, rows, exception);
+            NonNull::new(pixels).ok_or_else(|| Exception::from_exception_info(exception))
+        })?;
+
+        Ok(PixelRegion {
+            wand: PhantomData,
+            image,
+            pixels,
+            columns,
+            rows,
+            dirty: false,
+        })
+    }
+
+    /// Read the current image row by row, in the channel order specified by
+    ///
+    /// `map` (as [`MagickWand::get_image_pixels`]), see [Rows].
+    ///
+    pub fn rows<'s, T: MagickWandExportType>(
+        &mut self,
+        map: impl IntoNullTerminatedString<'s>,
+    ) -> Rows<'_, 'a, 's, T> {
+        let map = map.into_null_terminated_string();
+        let columns = self.get_image_width();
+        let rows = self.get_image_height();
+        let buffer = Vec::with_capacity(columns as usize * map.len());
+        Rows {
+            wand: self,
+            map,
+            columns,
+            rows,
+            y: 0,
+            buffer,
+        }
+    }
+
+    /// Read and write the current image row by row, in the channel order
+    ///
+    /// specified by `map` (as [`MagickWand::set_image_pixels`]), see [RowsMut].
+    ///
+    pub fn rows_mut<'s, T: MagickWandExportType>(
+        &mut self,
+        map: impl IntoNullTerminatedString<'s>,
+    ) -> RowsMut<'_, 'a, 's, T> {
+        let map = map.into_null_terminated_string();
+        let columns = self.get_image_width();
+        let rows = self.get_image_height();
+        let buffer = Vec::with_capacity(columns as usize * map.len());
+        RowsMut {
+            wand: self,
+            map,
+            columns,
+            rows,
+            y: 0,
+            buffer,
+            pending: None,
+        }
+    }
+
+    /// Walk the images of the sequence, see [Frames].
+    pub fn frames(&mut self) -> Frames<'_, 'a> {
+        Frames {
+            cursor: FrameCursor::new(self),
+        }
+    }
+
+    /// Walk and modify the images of the sequence, see [FramesMut].
+    pub fn frames_mut(&mut self) -> FramesMut<'_, 'a> {
+        FramesMut {
+            cursor: FrameCursor::new(self),
+        }
+    }
+}
+
+impl MagickWand<'_> {
+    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetimageprofile>
+    ///
+    /// MagickSetImageProfile() adds a named profile to the magick wand.  If a
+    ///
+    /// profile with the same name already exists, it is replaced.  This method
+    ///
+    /// differs from the MagickProfileImage() method in that it does not apply any
+    ///
+    /// CMS color profiles.
+    ///
+    pub fn set_image_profile<'s>(
+        &mut self,
+        name: impl IntoNullTerminatedString<'s>,
+        profile: impl IntoNullTerminatedString<'s>,
+        length: c_ulong,
+    ) -> crate::Result<&mut Self> {
+        let name = name.into_null_terminated_string();
+        let profile = profile.into_null_terminated_string();
+        let status = unsafe {
+            MagickSetImageProfile(
+                self.wand.as_ptr(),
+                name.as_ptr(),
+                profile.as_ptr().cast(),
+                length,
+            )
+        };
+        self.check_status(status)
+    }
+
+    /// Like [`MagickWand::set_image_profile`], but takes the profile as bytes,
+    ///
+    /// since binary profiles (ICC, EXIF, ...) may contain NUL bytes.
+    ///
+    pub fn set_image_profile_bytes<'s>(
+        &mut self,
+        name: impl IntoNullTerminatedString<'s>,
+        profile: &[u8],
+    ) -> crate::Result<&mut Self> {
+        let name = name.into_null_terminated_string();
+        let status = unsafe {
+            MagickSetImageProfile(
+                self.wand.as_ptr(),
+                name.as_ptr(),
+                profile.as_ptr(),
+                profile.len().try_into().unwrap(),
+            )
+        };
+        self.check_status(status)
+    }
+
+    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetimageredprimary>
+    ///
+    /// MagickSetImageRedPrimary() sets the image chromaticity red primary point.
+    ///
+    pub fn set_image_red_primary(&mut self, x: c_double, y: c_double) -> crate::Result<&mut Self> {
+        let status = unsafe { MagickSetImageRedPrimary(self.wand.as_ptr(), x, y) };
+        self.check_status(status)
+    }
+
+    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetimagerenderingintent>
+    ///
+    /// MagickSetImageRenderingIntent() sets the image rendering intent.
+    ///
+    pub fn set_image_rendering_intent(
+        &mut self,
+        rendering_intent: RenderingIntent,
+    ) -> crate::Result<&mut Self> {
+        let status =
+            unsafe { MagickSetImageRenderingIntent(self.wand.as_ptr(), rendering_intent.into()) };
+        self.check_status(status)
+    }
+
+    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetimageresolution>
+    ///
+    /// MagickSetImageResolution() sets the image resolution.
+    ///
+    pub fn set_image_resolution(
+        &mut self,
+        x_resolution: c_double,
+        y_resolution: c_double,
+    ) -> crate::Result<&mut Self> {
+        let status =
+            unsafe { MagickSetImageResolution(self.wand.as_ptr(), x_resolution, y_resolution) };
+        self.check_status(status)
+    }
+
+    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetimagescene>
+    ///
+    /// MagickSetImageScene() sets the image scene.
+    ///
+    pub fn set_image_scene(&mut self, scene: c_ulong) -> crate::Result<&mut Self> {
+        let status = unsafe { MagickSetImageScene(self.wand.as_ptr(), scene) };
+        self.check_status(status)
+    }
+
+    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetimagetype>
+    ///
+    /// MagickSetImageType() sets the image type.
+    ///
+    pub fn set_image_type(&mut self, image_type: ImageType) -> crate::Result<&mut Self> {
+        let status = unsafe { MagickSetImageType(self.wand.as_ptr(), image_type.into()) };
+        self.check_status(status)
+    }
+
+    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetimagesavedtype>
+    ///
+    /// MagickSetImageSavedType() sets the image type that will be used when the
+    ///
+    /// image is saved.
+    ///
+    pub fn set_image_saved_type(&mut self, image_type: ImageType) -> crate::Result<&mut Self> {
+        let status = unsafe { MagickSetImageSavedType(self.wand.as_ptr(), image_type.into()) };
+        self.check_status(status)
+    }
+
+    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetimageunits>
+    ///
+    /// MagickSetImageUnits() sets the image units of resolution.
+    ///
+    pub fn set_image_units(&mut self, units: ResolutionType) -> crate::Result<&mut Self> {
+        let status = unsafe { MagickSetImageUnits(self.wand.as_ptr(), units.into()) };
+        self.check_status(status)
+    }
+
+    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetimagevirtualpixelmethod>
+    ///
+    /// MagickSetImageVirtualPixelMethod() sets the image virtual pixel method.
+    ///
+    pub fn set_image_virtual_pixel_method(
+        &mut self,
+        method: VirtualPixelMethod,
+    ) -> crate::Result<&mut Self> {
+        let status = unsafe { MagickSetImageVirtualPixelMethod(self.wand.as_ptr(), method.into()) };
+        self.check_status(status)
+    }
+
+    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetinterlacescheme>
+    ///
+    /// MagickSetInterlaceScheme() sets the interlace scheme used when writing
+    ///
+    /// the image.
+    ///
+    pub fn set_interlace_scheme(
+        &mut self,
+        interlace_scheme: InterlaceType,
+    ) -> crate::Result<&mut Self> {
+        let status =
+            unsafe { MagickSetInterlaceScheme(self.wand.as_ptr(), interlace_scheme.into()) };
+        self.check_status(status)
+    }
+
+    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetresolution>
+    ///
+    /// MagickSetResolution() sets the resolution (density) of the magick wand.
+    ///
+    /// Set it before you read an EPS, PDF, or Postscript file in order to
+    ///
+    /// influence the size of the returned image, or after an image has already
+    ///
+    /// been created to influence the rendered image size when used with
+    ///
+    /// typesetting software.
+    ///
+    /// Also see MagickSetResolutionUnits() which specifies the units to use for
+    ///
+    /// the image resolution.
+    ///
+    pub fn set_resolution(
+        &mut self,
+        x_resolution: c_double,
+        y_resolution: c_double,
+    ) -> crate::Result<&mut Self> {
+        let status = unsafe { MagickSetResolution(self.wand.as_ptr(), x_resolution, y_resolution) };
+        self.check_status(status)
+    }
+
+    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magicksetresolutionunits>
+    ///
+    /// MagickSetResolutionUnits() sets the resolution units of the magick wand.
+    ///
+    /// It should be used in conjunction with MagickSetResolution().
+    ///
+    /// This method works both before and after an image has been read.
+    ///
+    /// Also see MagickSetImageUnits() which specifies the units which apply to
+    ///
+    /// the image resolution setting after an image has been read.
+    ///
     pub fn set_resolution_units(&mut self, units: ResolutionType) -> crate::Result<&mut Self> {
         let status = unsafe { MagickSetResolutionUnits(self.wand.as_ptr(), units.into()) };
         self.check_status(status)
@@ -3730,10 +4698,11 @@ impl MagickWand<'_> {
     ///
     pub fn shear_image(
         &mut self,
-        background: &PixelWand,
+        background: impl AsPixelWand,
         x_shear: c_double,
         y_shear: c_double,
     ) -> crate::Result<&mut Self> {
+        let background = background.as_pixel_wand();
         let status =
             unsafe { MagickShearImage(self.wand.as_ptr(), background.wand(), x_shear, y_shear) };
         self.check_status(status)
@@ -3871,9 +4840,11 @@ impl MagickWand<'_> {
     ///
     pub fn tint_image(
         &mut self,
-        tint: &PixelWand,
-        opacity: &PixelWand,
+        tint: impl AsPixelWand,
+        opacity: impl AsPixelWand,
     ) -> crate::Result<&mut Self> {
+        let tint = tint.as_pixel_wand();
+        let opacity = opacity.as_pixel_wand();
         let status = unsafe { MagickTintImage(self.wand.as_ptr(), tint.wand(), opacity.wand()) };
         self.check_status(status)
     }
@@ -3888,10 +4859,12 @@ impl MagickWand<'_> {
     ///
     /// original image handle is returned.
     ///
+    /// The geometries may be given as [Geometry](crate::geometry::Geometry).
+    ///
     pub fn transform_image<'s>(
         &mut self,
         crop: impl IntoNullTerminatedString<'s>,
-        geometry: &str,
+        geometry: impl IntoNullTerminatedString<'s>,
     ) -> Option<MagickWand<'_>> {
         let crop = crop.into_null_terminated_string();
         let geometry = geometry.into_null_terminated_string();
@@ -3908,10 +4881,11 @@ impl MagickWand<'_> {
     ///
     pub fn transparent_image(
         &mut self,
-        target: &PixelWand,
+        target: impl AsPixelWand,
         opacity: Quantum,
         fuzz: c_double,
     ) -> crate::Result<&mut Self> {
+        let target = target.as_pixel_wand();
         let status =
             unsafe { MagickTransparentImage(self.wand.as_ptr(), target.wand(), opacity, fuzz) };
         self.check_status(status)
@@ -3973,7 +4947,11 @@ impl MagickWand<'_> {
     ///
     /// unchanged.
     ///
-    pub fn white_threshold_image(&mut self, threshold: &PixelWand) -> crate::Result<&mut Self> {
+    pub fn white_threshold_image(
+        &mut self,
+        threshold: impl AsPixelWand,
+    ) -> crate::Result<&mut Self> {
+        let threshold = threshold.as_pixel_wand();
         let status = unsafe { MagickWhiteThresholdImage(self.wand.as_ptr(), threshold.wand()) };
         self.check_status(status)
     }
@@ -3988,28 +4966,9 @@ impl MagickWand<'_> {
     ) -> crate::Result<&mut Self> {
         let filename = filename.into_null_terminated_string();
         let status = unsafe { MagickWriteImage(self.wand.as_ptr(), filename.as_ptr()) };
-        self.check_status(status)
+        self.check_coder_status(status)
     }
 
-    // Not need
-    //    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magickwriteimagesfile>
-    //    ///
-    //    /// MagickWriteImagesFile() writes an image or image sequence to a stdio
-    //    ///
-    //    /// FILE handle.  This may be used to append an encoded image to an already
-    //    ///
-    //    /// existing appended image sequence if the file seek position is at the end
-    //    ///
-    //    /// of an existing file.
-    //    ///
-    //    pub fn write_images_file(
-    //        &mut self,
-    //        file: &mut File,
-    //        adjoin: c_uint,
-    //    ) -> crate::Result<&mut Self> {
-    //        // let status = unsafe { MagickWriteImagesFile(self.wand.as_ptr(), file,  adjoin) };
-    //    }
-
     /// <http://www.graphicsmagick.org/wand/magick_wand.html#magickwriteimageblob>
     ///
     /// MagickWriteImageBlob() implements direct to memory image formats.  It
@@ -4031,17 +4990,68 @@ impl MagickWand<'_> {
     pub fn write_image_blob(&mut self) -> Option<MagickBoxSlice<u8>> {
         let mut length = 0;
         let ptr = unsafe { MagickWriteImageBlob(self.wand.as_ptr(), &mut length) };
-        unsafe { MagickBoxSlice::new(ptr, length.try_into().unwrap()) }
+        let blob = unsafe { MagickBoxSlice::new(ptr, length.try_into().unwrap()) };
+        if blob.is_some() {
+            unsafe { self.collect_warning() };
+        }
+        blob
     }
 
-    // Not need
-    //    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magickwriteimagefile>
-    //    ///
-    //    /// MagickWriteImageFile() writes an image to an open file descriptor.
-    //    ///
-    //    pub fn write_image_file(&mut self, file: &mut File) -> crate::Result<&mut Self> {
-    //        // let status = unsafe { MagickWriteImageFile(self.wand.as_ptr(), file) };
-    //    }
+    /// <http://www.graphicsmagick.org/wand/magick_wand.html#magickwriteimagesfile>
+    ///
+    /// MagickWriteImagesFile() writes an image or image sequence to a stdio
+    ///
+    /// FILE handle, here the write end of a pipe, which a scoped thread drains
+    ///
+    /// into `writer`, then flushes `writer`, so the encoded output is never
+    ///
+    /// buffered as a whole. The sequence is written from its first frame, adjoined
+    ///
+    /// if the format supports it. Coders which need to seek are written through a
+    ///
+    /// temporary file by GraphicsMagick.
+    ///
+    #[cfg(unix)]
+    pub fn write_image_to<W: Write + Send>(&mut self, mut writer: W) -> crate::Result<&mut Self> {
+        let (mut output, file) = crate::utils::write_pipe()?;
+        let wand = self.wand.as_ptr();
+        let (status, closed, copied) = thread::scope(|scope| {
+            let copier = scope.spawn(move || {
+                let copied = io::copy(&mut output, &mut writer).and_then(|_| writer.flush());
+                if copied.is_err() {
+                    // Keep the read end open until GraphicsMagick is done, or
+                    // its next write would raise SIGPIPE.
+                    io::copy(&mut output, &mut io::sink())?;
+                }
+                copied
+            });
+            let status = unsafe { MagickWriteImagesFile(wand, file.as_ptr(), MagickTrue) };
+            // Closing the write end flushes the `FILE`, and ends the copier.
+            let closed = file.close();
+            (status, closed, copier.join())
+        });
+        copied.unwrap_or_else(|payload| panic::resume_unwind(payload))?;
+        self.check_coder_status(status)?;
+        closed?;
+        Ok(self)
+    }
+
+    /// Buffered fallback of [MagickWand::write_image_blob] for targets without
+    ///
+    /// pipes: the whole sequence is encoded in memory, from its first frame, then
+    ///
+    /// written into `writer`.
+    ///
+    #[cfg(not(unix))]
+    pub fn write_image_to<W: Write + Send>(&mut self, mut writer: W) -> crate::Result<&mut Self> {
+        self.reset_iterator();
+        let Some(blob) = self.write_image_blob() else {
+            return Err(unsafe { self.get_error() });
+        };
+        writer.write_all(&blob)?;
+        writer.flush()?;
+        Ok(self)
+    }
 
     /// <http://www.graphicsmagick.org/wand/magick_wand.html#magickwriteimages>
     ///
@@ -4058,13 +5068,14 @@ impl MagickWand<'_> {
     ) -> crate::Result<&mut Self> {
         let filename = filename.into_null_terminated_string();
         let status = unsafe { MagickWriteImages(self.wand.as_ptr(), filename.as_ptr(), adjoin) };
-        self.check_status(status)
+        self.check_coder_status(status)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
+        error::{ExceptionCategory, ExceptionSeverity, ExceptionType},
         initialize,
         tests::{logo_path, logo_unicode_path},
         types::*,
@@ -4075,8 +5086,9 @@ mod tests {
     };
     use std::{
         fs::File,
-        io::Read,
+        io::{self, Read, Write},
         os::raw::{c_double, c_float, c_uchar, c_uint, c_ulong, c_ushort},
+        thread,
     };
 
     fn new_magick_wand() -> MagickWand<'static> {
@@ -4095,6 +5107,42 @@ mod tests {
         let _mw = new_magick_wand();
     }
 
+    #[test]
+    fn test_magick_wand_send() {
+        let mw = new_logo_magick_wand();
+
+        let mut mw = thread::spawn(move || {
+            let mut mw = mw;
+            mw.resize_image(100, 100, FilterTypes::UndefinedFilter, 1.)
+                .unwrap();
+            mw
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!((mw.get_image_width(), mw.get_image_height()), (100, 100));
+    }
+
+    #[test]
+    fn test_magick_wand_send_many_threads() {
+        let handles = (0..8)
+            .map(|i| {
+                let mw = new_logo_magick_wand();
+                thread::spawn(move || {
+                    let mut mw = mw;
+                    mw.resize_image(10 + i, 10 + i, FilterTypes::UndefinedFilter, 1.)
+                        .unwrap();
+                    (i, mw)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            let (i, mut mw) = handle.join().unwrap();
+            assert_eq!(mw.get_image_width(), 10 + i);
+        }
+    }
+
     #[test]
     fn test_magick_wand_read_image() {
         let mut mw = new_magick_wand();
@@ -4119,6 +5167,175 @@ mod tests {
         assert!(mw.read_image_blob(content).is_err());
     }
 
+    #[test]
+    fn test_magick_wand_warnings() {
+        let mut mw = new_logo_magick_wand();
+        mw.set_image_format("JPEG").unwrap();
+        let blob = mw.write_image_blob().unwrap();
+        assert!(mw.take_warnings().is_empty());
+
+        // Truncated in the middle of the scan data.
+        let truncated = &blob[..blob.len() * 2 / 3];
+        let mut mw = new_magick_wand();
+        mw.read_image_blob(truncated).unwrap();
+        assert!(!mw.warnings().is_empty());
+        assert!(
+            mw.warnings()
+                .iter()
+                .all(|warning| warning.severity() == ExceptionSeverity::Warning)
+        );
+
+        let warnings = mw.take_warnings();
+        assert!(!warnings.is_empty());
+        assert!(mw.warnings().is_empty());
+
+        // The warning isn't reported again by the next coder call, which
+        // collects warnings too.
+        mw.set_image_format("PNG").unwrap();
+        assert!(mw.write_image_blob().is_some());
+        assert!(mw.take_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_magick_wand_clear_exception() {
+        let mut mw = new_magick_wand();
+        assert!(mw.read_image("/not/exists/logo.png").is_err());
+        // Already cleared after the error is extracted.
+        let exception = unsafe { mw.get_exception() };
+        assert_eq!(
+            exception.get_exception_type(),
+            ExceptionType::UndefinedException
+        );
+        mw.clear_exception();
+    }
+
+    #[test]
+    fn test_magick_wand_reuse_after_error() {
+        let mut mw = new_magick_wand();
+        let err = mw.read_image("/not/exists/logo.png").unwrap_err();
+        let reason = err.exception().unwrap().get_reason().to_owned();
+
+        mw.read_image(logo_unicode_path()).unwrap();
+        assert!(mw.take_warnings().is_empty());
+        assert_eq!(mw.get_image_width(), 1024);
+
+        mw.resize_image(10, 10, FilterTypes::LanczosFilter, 1.)
+            .unwrap();
+        assert_eq!(mw.get_image_width(), 10);
+
+        // Must not report the stale exception of the first read.
+        let err = mw.read_image_blob(b"....").unwrap_err();
+        let exception = err.exception().unwrap();
+        assert_ne!(
+            (exception.get_reason(), exception.get_description()),
+            (reason.as_str(), Some("/not/exists/logo.png"))
+        );
+    }
+
+    #[test]
+    fn test_magick_wand_error() {
+        let mut mw = new_magick_wand();
+        let err = mw.read_image("/not/exists/logo.png").unwrap_err();
+        assert_eq!(err.severity(), Some(ExceptionSeverity::Error));
+        assert!(!err.is_retryable());
+        let exception = err.exception().unwrap();
+        assert!(!exception.get_reason().is_empty());
+        assert_ne!(exception.category(), ExceptionCategory::Undefined);
+    }
+
+    #[test]
+    fn test_magick_wand_read_image_from() {
+        let mut mw = new_magick_wand();
+        mw.read_image_from(File::open(logo_path()).unwrap())
+            .unwrap();
+        assert_eq!(mw.get_image_width(), 1024);
+        assert_eq!(mw.get_image_height(), 1024);
+    }
+
+    #[test]
+    fn test_magick_wand_read_image_from_failed() {
+        struct FailedReader;
+
+        impl Read for FailedReader {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::other("broken"))
+            }
+        }
+
+        let mut mw = new_magick_wand();
+        assert!(matches!(
+            mw.read_image_from(FailedReader),
+            Err(crate::Error::Io(_))
+        ));
+        assert!(matches!(
+            mw.read_image_from(&b"...."[..]),
+            Err(crate::Error::Exception(_))
+        ));
+    }
+
+    #[test]
+    fn test_magick_wand_read_image_from_trailing_data() {
+        let mut mw = new_logo_magick_wand();
+        mw.set_image_format("PNG").unwrap();
+        let mut png = mw.write_image_blob().unwrap().to_vec();
+        // Still being written by the copier once the coder is done, far more
+        // than a pipe buffers.
+        png.resize(png.len() + (1 << 20), 0);
+
+        let mut mw = new_magick_wand();
+        mw.read_image_from(png.as_slice()).unwrap();
+        assert_eq!(mw.get_image_width(), 1024);
+    }
+
+    #[test]
+    fn test_magick_wand_stream_larger_than_pipe() {
+        /// Hands out at most 4 KiB per call, as a socket would.
+        struct ChunkedReader<'a>(&'a [u8]);
+
+        impl Read for ChunkedReader<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                let n = buf.len().min(4096).min(self.0.len());
+                buf[..n].copy_from_slice(&self.0[..n]);
+                self.0 = &self.0[n..];
+                Ok(n)
+            }
+        }
+
+        #[derive(Default)]
+        struct CountingWriter {
+            bytes: usize,
+            writes: usize,
+        }
+
+        impl Write for CountingWriter {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.bytes += buf.len();
+                self.writes += 1;
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut mw = new_logo_magick_wand();
+        mw.set_image_format("PPM").unwrap();
+        let ppm = mw.write_image_blob().unwrap().to_vec();
+        // Far more than the 64 KiB a pipe buffers on Linux.
+        assert!(ppm.len() > 1 << 20);
+
+        let mut mw = new_magick_wand();
+        mw.read_image_from(ChunkedReader(&ppm)).unwrap();
+        assert_eq!(mw.get_image_width(), 1024);
+        assert_eq!(mw.get_image_height(), 1024);
+
+        let mut writer = CountingWriter::default();
+        mw.write_image_to(&mut writer).unwrap();
+        assert_eq!(writer.bytes, ppm.len());
+        assert!(writer.writes > 1);
+    }
+
     #[test]
     #[should_panic]
     fn test_magick_wand_adaptive_threshold_image_with_zero_width() {
@@ -4714,12 +5931,279 @@ mod tests {
         test_magick_wand_get_set_image_pixels_inner::<c_double>();
     }
 
+    #[test]
+    fn test_magick_wand_pixel_region() {
+        let mut mw = new_logo_magick_wand();
+        let before = mw.get_image_pixels::<c_uchar>(0, 0, 10, 10, "RGB").unwrap();
+
+        let mut region = mw.pixel_region(2, 3, 4, 5).unwrap();
+        assert_eq!(region.columns(), 4);
+        assert_eq!(region.rows(), 5);
+        assert_eq!(region.pixels().len(), 20);
+        assert_eq!(region.iter_rows().count(), 5);
+        assert!(region.iter_rows().all(|row| row.len() == 4));
+        for pixel in region.row_mut(1) {
+            pixel.red = Quantum::MAX;
+            pixel.green = 0;
+            pixel.blue = 0;
+        }
+        region.commit().unwrap();
+
+        let after = mw.get_image_pixels::<c_uchar>(0, 0, 10, 10, "RGB").unwrap();
+        for y in 0..10 {
+            for x in 0..10 {
+                let i = (y * 10 + x) * 3;
+                if y == 4 && (2..6).contains(&x) {
+                    assert_eq!(&after[i..i + 3], &[255, 0, 0]);
+                } else {
+                    assert_eq!(&after[i..i + 3], &before[i..i + 3]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_magick_wand_pixel_region_drop() {
+        let mut mw = new_logo_magick_wand();
+        let pixel = mw.pixel_region(0, 0, 1, 1).unwrap().pixels()[0];
+
+        {
+            let mut region = mw.pixel_region(0, 0, 2, 2).unwrap();
+            for row in region.iter_rows_mut() {
+                for pixel in row {
+                    pixel.green = if pixel.green == 0 { Quantum::MAX } else { 0 };
+                }
+            }
+        }
+
+        let changed = mw.pixel_region(0, 0, 1, 1).unwrap().pixels()[0];
+        assert_eq!(changed.red, pixel.red);
+        assert_ne!(changed.green, pixel.green);
+        let exported = mw.get_image_pixels::<Quantum>(0, 0, 1, 1, "G").unwrap();
+        assert_eq!(exported, [changed.green]);
+    }
+
+    #[test]
+    fn test_magick_wand_pixel_region_read_only() {
+        let mut mw = new_logo_magick_wand();
+        mw.set_image_format("GIF").unwrap();
+        let gif = mw.write_image_blob().unwrap().to_vec();
+
+        let mut mw = new_magick_wand();
+        mw.read_image_from(gif.as_slice()).unwrap();
+        let image_type = mw.get_image_type();
+        assert!(matches!(
+            image_type,
+            ImageType::PaletteType | ImageType::PaletteMatteType
+        ));
+
+        let region = mw.pixel_region(0, 0, 2, 2).unwrap();
+        assert_eq!(region.pixels().len(), 4);
+        region.commit().unwrap();
+        drop(mw.pixel_region(0, 0, 2, 2).unwrap());
+        assert_eq!(mw.get_image_type(), image_type);
+    }
+
+    #[test]
+    fn test_magick_wand_pixel_region_out_of_image() {
+        let mut mw = new_logo_magick_wand();
+        let columns = mw.get_image_width();
+        assert!(mw.pixel_region(0, 0, columns, 1).is_ok());
+        assert!(mw.pixel_region(1, 0, columns, 1).is_err());
+        assert!(mw.pixel_region(-1, 0, 1, 1).is_err());
+
+        let mut mw = new_magick_wand();
+        assert!(mw.pixel_region(0, 0, 1, 1).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_magick_wand_pixel_region_row_out_of_region() {
+        let mut mw = new_logo_magick_wand();
+        let region = mw.pixel_region(0, 0, 2, 2).unwrap();
+        region.row(2);
+    }
+
+    #[test]
+    fn test_magick_wand_rows() {
+        let mut mw = new_logo_magick_wand();
+        let columns = mw.get_image_width();
+        let expected = mw
+            .get_image_pixels::<c_uchar>(0, 0, columns, 3, "RGB")
+            .unwrap();
+
+        let mut rows = mw.rows::<c_uchar>("RGB");
+        assert_eq!(rows.columns(), 1024);
+        assert_eq!(rows.rows(), 1024);
+
+        let mut count = 0;
+        while let Some(row) = rows.next_row() {
+            let row = row.unwrap();
+            assert_eq!(row.len(), columns as usize * 3);
+            if count < 3 {
+                let start = count * columns as usize * 3;
+                assert_eq!(row, &expected[start..start + row.len()]);
+            }
+            count += 1;
+        }
+        assert_eq!(count, 1024);
+        assert!(rows.next_row().is_none());
+    }
+
+    #[test]
+    fn test_magick_wand_rows_mut() {
+        let mut mw = new_logo_magick_wand();
+        let columns = mw.get_image_width();
+
+        let mut rows = mw.rows_mut::<c_uchar>("RGB");
+        while let Some(row) = rows.next_row() {
+            row.unwrap().fill(0);
+        }
+        rows.finish().unwrap();
+
+        let pixels = mw
+            .get_image_pixels::<c_uchar>(0, 0, columns, 1024, "RGB")
+            .unwrap();
+        assert!(pixels.iter().all(|&p| p == 0));
+
+        // The last returned row is written back on drop.
+        {
+            let mut rows = mw.rows_mut::<c_uchar>("R");
+            rows.next_row().unwrap().unwrap().fill(255);
+            rows.next_row().unwrap().unwrap().fill(255);
+            assert_eq!(rows.y(), 2);
+        }
+        let pixels = mw
+            .get_image_pixels::<c_uchar>(0, 0, columns, 3, "R")
+            .unwrap();
+        let columns = columns as usize;
+        assert!(pixels[..2 * columns].iter().all(|&p| p == 255));
+        assert!(pixels[2 * columns..].iter().all(|&p| p == 0));
+    }
+
+    fn new_sequence_magick_wand() -> MagickWand<'static> {
+        let mut mw = new_magick_wand();
+        mw.set_size(4, 4).unwrap();
+        mw.read_image("xc:red").unwrap();
+        mw.set_size(8, 2).unwrap();
+        mw.read_image("xc:green").unwrap();
+        mw.set_size(2, 8).unwrap();
+        mw.read_image("xc:blue").unwrap();
+        mw
+    }
+
+    #[test]
+    fn test_magick_wand_frames() {
+        let mut mw = new_sequence_magick_wand();
+        mw.set_image_index(1).unwrap();
+
+        let mut sizes = Vec::new();
+        let mut frames = mw.frames();
+        assert_eq!(frames.len(), 3);
+        while let Some(mut frame) = frames.next_frame() {
+            sizes.push((frame.index(), frame.width(), frame.height()));
+            frame.page().unwrap();
+        }
+        assert!(frames.next_frame().is_none());
+        drop(frames);
+
+        assert_eq!(sizes, [(0, 4, 4), (1, 8, 2), (2, 2, 8)]);
+        assert_eq!(mw.get_image_index(), 1);
+
+        let mut mw = new_magick_wand();
+        let mut frames = mw.frames();
+        assert!(frames.is_empty());
+        assert!(frames.next_frame().is_none());
+    }
+
+    #[test]
+    fn test_magick_wand_frames_mut() {
+        let mut mw = new_sequence_magick_wand();
+        mw.set_image_index(2).unwrap();
+
+        {
+            let mut frames = mw.frames_mut();
+            // Stop early, the cursor is restored anyway.
+            let mut frame = frames.next_frame().unwrap();
+            frame
+                .set_delay(10)
+                .unwrap()
+                .set_dispose(DisposeType::BackgroundDispose)
+                .unwrap()
+                .set_page(16, 16, 1, 2)
+                .unwrap()
+                .set_scene(5)
+                .unwrap();
+            assert_eq!(frame.delay(), 10);
+        }
+        assert_eq!(mw.get_image_index(), 2);
+
+        let mut frames = mw.frames_mut();
+        while let Some(mut frame) = frames.next_frame() {
+            if frame.index() == 0 {
+                assert_eq!(frame.delay(), 10);
+                assert_eq!(frame.dispose(), DisposeType::BackgroundDispose);
+                assert_eq!(frame.page().unwrap(), (16, 16, 1, 2));
+                assert_eq!(frame.scene(), 5);
+            } else {
+                assert_eq!(frame.delay(), 0);
+            }
+        }
+    }
+
     #[test]
     fn test_magick_wand_get_image_profile() {
         let mut mw = new_logo_magick_wand();
         mw.get_image_profile("ICM").to_str().unwrap();
     }
 
+    #[test]
+    fn test_magick_wand_get_image_profile_bytes() {
+        let mut mw = new_logo_magick_wand();
+        assert!(mw.get_image_profile_bytes("APP1").is_none());
+
+        let profile = b"Exif\0\0MM\0*\0\0\0\x08";
+        mw.set_image_profile_bytes("APP1", profile).unwrap();
+        assert_eq!(&*mw.get_image_profile_bytes("APP1").unwrap(), profile);
+        // The string getter stops at the first NUL byte.
+        assert_eq!(mw.get_image_profile("APP1").to_str().unwrap(), "Exif");
+    }
+
+    #[test]
+    fn test_magick_wand_get_image_profile_names() {
+        let mut mw = new_logo_magick_wand();
+        mw.set_image_profile_bytes("APP1", b"Exif\0\0").unwrap();
+        mw.set_image_profile_bytes("XMP", b"<x:xmpmeta/>").unwrap();
+        let names = mw.get_image_profile_names();
+        assert!(names.iter().any(|name| name == "APP1"));
+        assert!(names.iter().any(|name| name == "XMP"));
+        mw.remove_image_profile("XMP");
+        assert!(
+            !mw.get_image_profile_names()
+                .iter()
+                .any(|name| name == "XMP")
+        );
+
+        let mut mw = new_magick_wand();
+        assert!(mw.get_image_profile_names().is_empty());
+        let exception = unsafe { mw.get_exception() };
+        assert_eq!(
+            exception.get_exception_type(),
+            ExceptionType::UndefinedException
+        );
+    }
+
+    #[test]
+    fn test_magick_wand_get_image_profile_bytes_no_image() {
+        let mut mw = new_magick_wand();
+        assert!(mw.get_image_profile_bytes("EXIF").is_none());
+        let exception = unsafe { mw.get_exception() };
+        assert_eq!(
+            exception.get_exception_type(),
+            ExceptionType::UndefinedException
+        );
+    }
+
     #[test]
     fn test_magick_wand_get_image_red_primary() {
         let mut mw = new_logo_magick_wand();
@@ -5033,6 +6517,13 @@ mod tests {
         mw.profile_image("", "", 0).unwrap();
     }
 
+    #[test]
+    fn test_magick_wand_profile_image_bytes() {
+        let mut mw = new_logo_magick_wand();
+        mw.profile_image_bytes("APP1", b"Exif\0\0").unwrap();
+        assert_eq!(&*mw.get_image_profile_bytes("APP1").unwrap(), b"Exif\0\0");
+    }
+
     #[test]
     fn test_magick_wand_quantize_image() {
         let mut mw = new_logo_magick_wand();
@@ -5576,6 +7067,67 @@ mod tests {
         mw.write_image_blob().unwrap();
     }
 
+    #[test]
+    fn test_magick_wand_write_image_to() {
+        let mut mw = new_logo_magick_wand();
+        mw.set_image_format("PNG").unwrap();
+        let mut buf = Vec::new();
+        mw.write_image_to(&mut buf).unwrap();
+        assert!(buf.starts_with(b"\x89PNG"));
+
+        let mut mw = new_magick_wand();
+        mw.read_image_from(buf.as_slice()).unwrap();
+        assert_eq!(mw.get_image_width(), 1024);
+        assert_eq!(mw.get_image_format().to_str().unwrap(), "PNG");
+    }
+
+    #[test]
+    fn test_magick_wand_write_image_to_sequence() {
+        let mut mw = new_magick_wand();
+        mw.set_size(8, 8).unwrap();
+        for color in ["xc:red", "xc:green", "xc:blue"] {
+            mw.read_image(color).unwrap();
+        }
+        mw.reset_iterator();
+        mw.set_image_format("GIF").unwrap();
+        let mut buf = Vec::new();
+        mw.write_image_to(&mut buf).unwrap();
+
+        let mut mw = new_magick_wand();
+        mw.read_image_from(buf.as_slice()).unwrap();
+        assert_eq!(mw.get_number_images(), 3);
+    }
+
+    #[test]
+    fn test_magick_wand_write_image_to_failed() {
+        struct FailedWriter;
+
+        impl Write for FailedWriter {
+            fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+                Err(io::Error::other("broken"))
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut mw = new_magick_wand();
+        assert!(matches!(
+            mw.write_image_to(Vec::new()),
+            Err(crate::Error::Exception(_))
+        ));
+
+        // Far more than a pipe buffers, so GraphicsMagick still writes after
+        // the writer failed.
+        let mut mw = new_logo_magick_wand();
+        mw.set_image_format("PPM").unwrap();
+        assert!(matches!(
+            mw.write_image_to(FailedWriter),
+            Err(crate::Error::Io(_))
+        ));
+    }
+
     #[test]
     fn test_magick_wand_write_images() {
         let mut mw = new_logo_magick_wand();
@@ -2,8 +2,15 @@
 //!
 //! <http://www.graphicsmagick.org/wand/wand.html>
 
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub mod async_magick;
 pub mod drawing;
 pub mod magick;
 pub mod pixel;
 
 pub use self::{drawing::DrawingWand, magick::MagickWand, pixel::PixelWand};
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub use self::async_magick::AsyncMagickWand;
@@ -56,6 +56,14 @@ impl Drop for PixelWand {
     }
 }
 
+// Safety:
+//
+// A `PixelWand` is a heap allocated colour value plus its own
+// `ExceptionInfo`, with no reference to thread-local state, so it can be
+// dropped or used from any thread as long as only one thread touches it at
+// a time, which `&mut self` already guarantees.
+unsafe impl Send for PixelWand {}
+
 impl Clone for PixelWand {
     fn clone(&self) -> Self {
         PixelWand {
@@ -428,12 +436,30 @@ impl PixelWand {
 #[cfg(test)]
 mod tests {
     use crate::{initialize, types::PixelPacket, wand::PixelWand};
+    use std::thread;
 
     fn new_pixel_wand() -> PixelWand {
         initialize();
         PixelWand::new()
     }
 
+    #[test]
+    fn test_pixel_wand_send() {
+        let mut pw = new_pixel_wand();
+        pw.set_color("red");
+
+        let pw = thread::spawn(move || {
+            let mut pw = pw;
+            pw.set_blue(1.);
+            pw
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(pw.get_red(), 1.);
+        assert_eq!(pw.get_blue(), 1.);
+    }
+
     #[test]
     fn test_pixel_wand_get_black() {
         let pw = new_pixel_wand();
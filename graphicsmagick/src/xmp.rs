@@ -0,0 +1,859 @@
+//! Typed reading and editing of the XMP profile.
+//!
+//! [Xmp] parses the RDF/XML packet of the `XMP` profile of an image into
+//! properties, keyed by namespace and name, and serializes them back into a
+//! fresh packet.
+//!
+//! ```no_run
+//! use graphicsmagick::{initialize, wand::MagickWand, xmp::Xmp};
+//!
+//! initialize();
+//!
+//! let mut mw = MagickWand::new();
+//! mw.read_image("photo.jpg").unwrap();
+//!
+//! let mut xmp = mw.get_xmp().unwrap().unwrap_or_default();
+//! xmp.set_creators(["Jane Doe"]);
+//! xmp.set_rights("© 2024 Jane Doe, all rights reserved");
+//! mw.set_xmp(&xmp).unwrap();
+//! mw.write_image("photo-tagged.jpg").unwrap();
+//! ```
+//!
+//! Simple properties, language alternatives and arrays are typed, see
+//! [XmpValue]. Other properties, such as structures, are kept verbatim as
+//! [XmpValue::Raw].
+
+use crate::wand::MagickWand;
+use std::{collections::BTreeMap, fmt::Write};
+use thiserror::Error as ThisError;
+
+/// Name of the XMP profile in GraphicsMagick.
+const PROFILE_NAME: &str = "XMP";
+
+/// Well known namespace URIs.
+pub mod ns {
+    pub const RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+    pub const XML: &str = "http://www.w3.org/XML/1998/namespace";
+    /// Dublin Core: title, description, creator, rights, subject, ...
+    pub const DC: &str = "http://purl.org/dc/elements/1.1/";
+    /// XMP basic: CreateDate, CreatorTool, Rating, ...
+    pub const XMP: &str = "http://ns.adobe.com/xap/1.0/";
+    /// XMP rights management: Marked, UsageTerms, WebStatement, ...
+    pub const XMP_RIGHTS: &str = "http://ns.adobe.com/xap/1.0/rights/";
+    /// Photoshop: Headline, Credit, Source, City, ...
+    pub const PHOTOSHOP: &str = "http://ns.adobe.com/photoshop/1.0/";
+    /// IPTC Core.
+    pub const IPTC_CORE: &str = "http://iptc.org/std/Iptc4xmpCore/1.0/xmlns/";
+}
+
+/// Prefixes used for the well known namespaces when writing.
+const DEFAULT_PREFIXES: [(&str, &str); 7] = [
+    (ns::RDF, "rdf"),
+    (ns::XML, "xml"),
+    (ns::DC, "dc"),
+    (ns::XMP, "xmp"),
+    (ns::XMP_RIGHTS, "xmpRights"),
+    (ns::PHOTOSHOP, "photoshop"),
+    (ns::IPTC_CORE, "Iptc4xmpCore"),
+];
+
+/// Default language of [XmpValue::Alt].
+const DEFAULT_LANGUAGE: &str = "x-default";
+
+/// Value of an XMP property.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum XmpValue {
+    /// Simple text value.
+    Text(String),
+    /// Language alternatives, as `(language, text)`, e.g.
+    /// `("x-default", "Title")`.
+    Alt(Vec<(String, String)>),
+    /// Ordered array.
+    Seq(Vec<String>),
+    /// Unordered array.
+    Bag(Vec<String>),
+    /// Property element kept verbatim, e.g. a structure. Its namespace
+    /// prefixes are the ones of the parsed packet.
+    Raw(String),
+}
+
+impl XmpValue {
+    /// Text of a [XmpValue::Text], or default text of a [XmpValue::Alt].
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            XmpValue::Text(text) => Some(text),
+            XmpValue::Alt(alternatives) => alternatives
+                .iter()
+                .find(|(language, _)| language == DEFAULT_LANGUAGE)
+                .or(alternatives.first())
+                .map(|(_, text)| &text[..]),
+            _ => None,
+        }
+    }
+
+    /// Items of a [XmpValue::Seq] or a [XmpValue::Bag], or a single
+    /// [XmpValue::Text].
+    pub fn as_list(&self) -> Option<Vec<&str>> {
+        match self {
+            XmpValue::Seq(items) | XmpValue::Bag(items) => {
+                Some(items.iter().map(|item| &item[..]).collect())
+            }
+            XmpValue::Text(text) => Some(vec![text]),
+            _ => None,
+        }
+    }
+}
+
+/// Error returned by [Xmp::parse].
+#[derive(ThisError, Debug, Clone, Eq, PartialEq)]
+#[error("invalid XMP packet: {reason} at byte {position}")]
+pub struct ParseXmpError {
+    reason: &'static str,
+    position: usize,
+}
+
+impl ParseXmpError {
+    /// Why the packet was rejected.
+    pub fn get_reason(&self) -> &'static str {
+        self.reason
+    }
+
+    /// Byte offset of the error in the packet.
+    pub fn get_position(&self) -> usize {
+        self.position
+    }
+}
+
+/// Properties of an XMP packet.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Xmp {
+    /// Keyed by `(namespace URI, name)`.
+    properties: BTreeMap<(String, String), XmpValue>,
+    /// Prefixes declared in the parsed packet, by namespace URI, which
+    /// [XmpValue::Raw] values may use.
+    prefixes: BTreeMap<String, String>,
+}
+
+impl Xmp {
+    /// Create a packet without any property.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Parse an XMP packet, with or without the `<?xpacket?>` wrapper and the
+    /// `x:xmpmeta` element.
+    pub fn parse(packet: &[u8]) -> Result<Self, ParseXmpError> {
+        let packet = packet.strip_prefix(b"\xef\xbb\xbf").unwrap_or(packet);
+        let packet = std::str::from_utf8(packet).map_err(|e| ParseXmpError {
+            reason: "invalid UTF-8",
+            position: e.valid_up_to(),
+        })?;
+        let root = parse_document(packet)?;
+
+        let mut xmp = Xmp::new();
+        let mut scope = Scope::default();
+        xmp.walk(packet, &root, &mut scope);
+        Ok(xmp)
+    }
+
+    /// Find `rdf:RDF` elements and read their descriptions.
+    fn walk(&mut self, packet: &str, element: &Element, scope: &mut Scope) {
+        scope.push(element, &mut self.prefixes);
+        if scope.resolve(&element.name) == Some((ns::RDF, "RDF")) {
+            for description in element.elements() {
+                self.read_description(packet, description, scope);
+            }
+        } else {
+            for child in element.elements() {
+                self.walk(packet, child, scope);
+            }
+        }
+        scope.pop();
+    }
+
+    fn read_description(&mut self, packet: &str, description: &Element, scope: &mut Scope) {
+        scope.push(description, &mut self.prefixes);
+        for (name, value) in &description.attributes {
+            if let Some((namespace, name)) = scope.resolve_property(name) {
+                self.insert(namespace, name, XmpValue::Text(value.clone()));
+            }
+        }
+        for property in description.elements() {
+            scope.push(property, &mut self.prefixes);
+            let resolved = scope
+                .resolve_property(&property.name)
+                .map(|(namespace, name)| (namespace.to_owned(), name));
+            if let Some((namespace, name)) = resolved {
+                let value = read_value(property, scope)
+                    .unwrap_or_else(|| XmpValue::Raw(packet[property.span.clone()].to_owned()));
+                self.insert(&namespace, name, value);
+            }
+            scope.pop();
+        }
+        scope.pop();
+    }
+
+    fn insert(&mut self, namespace: &str, name: &str, value: XmpValue) {
+        self.properties
+            .insert((namespace.to_owned(), name.to_owned()), value);
+    }
+
+    /// Serialize the properties into an XMP packet, as expected by
+    /// [MagickWand::set_xmp].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // Prefixes of the parsed packet first, as raw values depend on them.
+        let mut prefixes: BTreeMap<&str, String> = self
+            .prefixes
+            .iter()
+            .map(|(namespace, prefix)| (&namespace[..], prefix.clone()))
+            .collect();
+        for (namespace, _) in self.properties.keys() {
+            if prefixes.contains_key(&namespace[..]) {
+                continue;
+            }
+            let default = DEFAULT_PREFIXES
+                .iter()
+                .find(|(ns, _)| ns == namespace)
+                .map(|(_, prefix)| prefix.to_string());
+            let taken = |prefix: &str| prefixes.values().any(|p| p == prefix);
+            let prefix = default.filter(|prefix| !taken(prefix)).unwrap_or_else(|| {
+                (1..)
+                    .map(|i| format!("ns{}", i))
+                    .find(|prefix| !taken(prefix))
+                    .unwrap()
+            });
+            prefixes.insert(namespace, prefix);
+        }
+        let prefix = |namespace: &str| &prefixes[namespace];
+
+        let mut packet = String::new();
+        packet.push_str("<?xpacket begin=\"\u{feff}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\n");
+        packet.push_str("<x:xmpmeta xmlns:x=\"adobe:ns:meta/\">\n");
+        packet.push_str(" <rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\n");
+        packet.push_str("  <rdf:Description rdf:about=\"\"");
+        for (namespace, prefix) in &prefixes {
+            if !matches!(&prefix[..], "rdf" | "xml" | "x") {
+                write!(packet, "\n    xmlns:{}=\"{}\"", prefix, escape(namespace)).unwrap();
+            }
+        }
+        packet.push_str(">\n");
+
+        for ((namespace, name), value) in &self.properties {
+            let name = format!("{}:{}", prefix(namespace), name);
+            let items = |kind: &str, items: &[String], packet: &mut String| {
+                write!(packet, "   <{}>\n    <rdf:{}>\n", name, kind).unwrap();
+                for item in items {
+                    writeln!(packet, "     <rdf:li>{}</rdf:li>", escape(item)).unwrap();
+                }
+                write!(packet, "    </rdf:{}>\n   </{}>\n", kind, name).unwrap();
+            };
+            match value {
+                XmpValue::Text(text) => {
+                    writeln!(packet, "   <{}>{}</{}>", name, escape(text), name).unwrap()
+                }
+                XmpValue::Alt(alternatives) => {
+                    write!(packet, "   <{}>\n    <rdf:Alt>\n", name).unwrap();
+                    for (language, text) in alternatives {
+                        writeln!(
+                            packet,
+                            "     <rdf:li xml:lang=\"{}\">{}</rdf:li>",
+                            escape(language),
+                            escape(text)
+                        )
+                        .unwrap();
+                    }
+                    write!(packet, "    </rdf:Alt>\n   </{}>\n", name).unwrap();
+                }
+                XmpValue::Seq(values) => items("Seq", values, &mut packet),
+                XmpValue::Bag(values) => items("Bag", values, &mut packet),
+                XmpValue::Raw(raw) => writeln!(packet, "   {}", raw).unwrap(),
+            }
+        }
+
+        packet.push_str("  </rdf:Description>\n </rdf:RDF>\n</x:xmpmeta>\n");
+        packet.push_str("<?xpacket end=\"w\"?>");
+        packet.into_bytes()
+    }
+
+    /// Value of the property `name` of `namespace`, see [ns].
+    pub fn get(&self, namespace: &str, name: &str) -> Option<&XmpValue> {
+        self.properties
+            .get(&(namespace.to_owned(), name.to_owned()))
+    }
+
+    /// Set the value of the property `name` of `namespace`, returning the
+    /// previous one.
+    pub fn set(&mut self, namespace: &str, name: &str, value: XmpValue) -> Option<XmpValue> {
+        self.properties
+            .insert((namespace.to_owned(), name.to_owned()), value)
+    }
+
+    /// Remove the property `name` of `namespace`, returning its value.
+    pub fn remove(&mut self, namespace: &str, name: &str) -> Option<XmpValue> {
+        self.properties
+            .remove(&(namespace.to_owned(), name.to_owned()))
+    }
+
+    /// All properties, as `(namespace, name, value)`.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str, &XmpValue)> {
+        self.properties
+            .iter()
+            .map(|((namespace, name), value)| (&namespace[..], &name[..], value))
+    }
+
+    fn get_text(&self, namespace: &str, name: &str) -> Option<&str> {
+        self.get(namespace, name).and_then(XmpValue::as_text)
+    }
+
+    fn get_list(&self, namespace: &str, name: &str) -> Vec<&str> {
+        self.get(namespace, name)
+            .and_then(XmpValue::as_list)
+            .unwrap_or_default()
+    }
+
+    fn set_default_alt(&mut self, namespace: &str, name: &str, text: &str) {
+        let mut alternatives = match self.remove(namespace, name) {
+            Some(XmpValue::Alt(alternatives)) => alternatives,
+            _ => Vec::new(),
+        };
+        alternatives.retain(|(language, _)| language != DEFAULT_LANGUAGE);
+        alternatives.insert(0, (DEFAULT_LANGUAGE.to_owned(), text.to_owned()));
+        self.set(namespace, name, XmpValue::Alt(alternatives));
+    }
+
+    /// `dc:title`, in the default language.
+    pub fn title(&self) -> Option<&str> {
+        self.get_text(ns::DC, "title")
+    }
+
+    /// Set `dc:title` in the default language, keeping the translations.
+    pub fn set_title(&mut self, title: &str) {
+        self.set_default_alt(ns::DC, "title", title);
+    }
+
+    /// `dc:description`, in the default language.
+    pub fn description(&self) -> Option<&str> {
+        self.get_text(ns::DC, "description")
+    }
+
+    /// Set `dc:description` in the default language, keeping the
+    /// translations.
+    pub fn set_description(&mut self, description: &str) {
+        self.set_default_alt(ns::DC, "description", description);
+    }
+
+    /// `dc:rights`, in the default language.
+    pub fn rights(&self) -> Option<&str> {
+        self.get_text(ns::DC, "rights")
+    }
+
+    /// Set `dc:rights` in the default language, keeping the translations.
+    pub fn set_rights(&mut self, rights: &str) {
+        self.set_default_alt(ns::DC, "rights", rights);
+    }
+
+    /// `dc:creator`.
+    pub fn creators(&self) -> Vec<&str> {
+        self.get_list(ns::DC, "creator")
+    }
+
+    /// Replace `dc:creator` by an ordered list of `creators`.
+    pub fn set_creators<'c>(&mut self, creators: impl IntoIterator<Item = &'c str>) {
+        let creators = creators.into_iter().map(ToOwned::to_owned).collect();
+        self.set(ns::DC, "creator", XmpValue::Seq(creators));
+    }
+
+    /// `dc:subject`, i.e. keywords.
+    pub fn subjects(&self) -> Vec<&str> {
+        self.get_list(ns::DC, "subject")
+    }
+
+    /// Replace `dc:subject` by an unordered list of `subjects`.
+    pub fn set_subjects<'s>(&mut self, subjects: impl IntoIterator<Item = &'s str>) {
+        let subjects = subjects.into_iter().map(ToOwned::to_owned).collect();
+        self.set(ns::DC, "subject", XmpValue::Bag(subjects));
+    }
+
+    /// `xmpRights:UsageTerms`, in the default language.
+    pub fn usage_terms(&self) -> Option<&str> {
+        self.get_text(ns::XMP_RIGHTS, "UsageTerms")
+    }
+
+    /// Set `xmpRights:UsageTerms` in the default language, keeping the
+    /// translations.
+    pub fn set_usage_terms(&mut self, usage_terms: &str) {
+        self.set_default_alt(ns::XMP_RIGHTS, "UsageTerms", usage_terms);
+    }
+}
+
+/// Typed value of a property element, `None` if it must be kept raw.
+fn read_value(property: &Element, scope: &mut Scope) -> Option<XmpValue> {
+    let plain = |element: &Element, scope: &Scope| {
+        element.attributes.iter().all(|(name, _)| {
+            name.starts_with("xmlns") || scope.resolve(name) == Some((ns::XML, "lang"))
+        })
+    };
+    if !plain(property, scope) {
+        return None;
+    }
+
+    let mut containers = property.elements();
+    let Some(container) = containers.next() else {
+        return Some(XmpValue::Text(property.text()));
+    };
+    if containers.next().is_some() || !property.text().trim().is_empty() {
+        return None;
+    }
+
+    scope.push(container, &mut BTreeMap::new());
+    let kind = ["Seq", "Bag", "Alt"]
+        .into_iter()
+        .find(|kind| scope.resolve(&container.name) == Some((ns::RDF, kind)));
+    let mut items = Vec::new();
+    for item in container.elements() {
+        scope.push(item, &mut BTreeMap::new());
+        let is_li = scope.resolve(&item.name) == Some((ns::RDF, "li"));
+        let language = item
+            .attributes
+            .iter()
+            .find(|(name, _)| scope.resolve(name) == Some((ns::XML, "lang")))
+            .map(|(_, language)| language.clone());
+        let typed = is_li && plain(item, scope) && item.elements().next().is_none();
+        scope.pop();
+        if !typed {
+            scope.pop();
+            return None;
+        }
+        items.push((language, item.text()));
+    }
+    scope.pop();
+
+    let texts = || items.iter().map(|(_, text)| text.clone()).collect();
+    match kind {
+        Some("Seq") => Some(XmpValue::Seq(texts())),
+        Some("Bag") => Some(XmpValue::Bag(texts())),
+        Some("Alt") => Some(XmpValue::Alt(
+            items
+                .iter()
+                .map(|(language, text)| {
+                    let language = language.as_deref().unwrap_or(DEFAULT_LANGUAGE);
+                    (language.to_owned(), text.clone())
+                })
+                .collect(),
+        )),
+        _ => None,
+    }
+}
+
+/// Stack of namespace declarations of the ancestors of an element.
+#[derive(Default)]
+struct Scope {
+    frames: Vec<Vec<(String, String)>>,
+}
+
+impl Scope {
+    /// Enter `element`, recording its declarations in `prefixes` too, unless
+    /// the namespace or the prefix is already known.
+    fn push(&mut self, element: &Element, prefixes: &mut BTreeMap<String, String>) {
+        let declarations: Vec<_> = element
+            .attributes
+            .iter()
+            .filter_map(|(name, value)| {
+                let prefix = name.strip_prefix("xmlns:")?;
+                Some((prefix.to_owned(), value.clone()))
+            })
+            .collect();
+        for (prefix, namespace) in &declarations {
+            if !prefixes.contains_key(namespace) && !prefixes.values().any(|p| p == prefix) {
+                prefixes.insert(namespace.clone(), prefix.clone());
+            }
+        }
+        self.frames.push(declarations);
+    }
+
+    fn pop(&mut self) {
+        self.frames.pop();
+    }
+
+    /// Namespace URI and local name of the prefixed `name`.
+    fn resolve<'n>(&self, name: &'n str) -> Option<(&str, &'n str)> {
+        let (prefix, local) = name.split_once(':')?;
+        if prefix == "xml" {
+            return Some((ns::XML, local));
+        }
+        self.frames
+            .iter()
+            .rev()
+            .flatten()
+            .find(|(p, _)| p == prefix)
+            .map(|(_, namespace)| (&namespace[..], local))
+    }
+
+    /// Like [Scope::resolve], but `None` for RDF and XML syntax names.
+    fn resolve_property<'n>(&self, name: &'n str) -> Option<(&str, &'n str)> {
+        self.resolve(name)
+            .filter(|(namespace, _)| *namespace != ns::RDF && *namespace != ns::XML)
+    }
+}
+
+/// XML element of the packet.
+#[derive(Debug)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Node>,
+    /// Byte range of the whole element in the packet.
+    span: std::ops::Range<usize>,
+}
+
+#[derive(Debug)]
+enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|child| match child {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    fn text(&self) -> String {
+        self.children
+            .iter()
+            .filter_map(|child| match child {
+                Node::Text(text) => Some(&text[..]),
+                Node::Element(_) => None,
+            })
+            .collect()
+    }
+}
+
+/// Parse the subset of XML used by XMP packets into a document element
+/// holding the top level elements.
+fn parse_document(xml: &str) -> Result<Element, ParseXmpError> {
+    let error = |reason, position| ParseXmpError { reason, position };
+
+    let mut stack = vec![Element {
+        name: String::new(),
+        attributes: Vec::new(),
+        children: Vec::new(),
+        span: 0..xml.len(),
+    }];
+    let mut position = 0;
+    while position < xml.len() {
+        let rest = &xml[position..];
+        let skip = |end: &str| {
+            rest.find(end)
+                .map(|i| position + i + end.len())
+                .ok_or_else(|| error("unterminated markup", position))
+        };
+
+        if rest.starts_with("<?") {
+            position = skip("?>")?;
+        } else if rest.starts_with("<!--") {
+            position = skip("-->")?;
+        } else if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let end = cdata
+                .find("]]>")
+                .ok_or_else(|| error("unterminated markup", position))?;
+            let parent = stack.last_mut().unwrap();
+            parent.children.push(Node::Text(cdata[..end].to_owned()));
+            position = skip("]]>")?;
+        } else if rest.starts_with("<!") {
+            position = skip(">")?;
+        } else if let Some(end_tag) = rest.strip_prefix("</") {
+            let end = end_tag
+                .find('>')
+                .ok_or_else(|| error("unterminated markup", position))?;
+            let mut element = stack.pop().unwrap();
+            if stack.is_empty() || element.name != end_tag[..end].trim() {
+                return Err(error("mismatched end tag", position));
+            }
+            position += 2 + end + 1;
+            element.span.end = position;
+            stack
+                .last_mut()
+                .unwrap()
+                .children
+                .push(Node::Element(element));
+        } else if rest.starts_with('<') {
+            let (element, end, empty) = parse_start_tag(xml, position)?;
+            position = end;
+            if empty {
+                let parent = stack.last_mut().unwrap();
+                parent.children.push(Node::Element(element));
+            } else {
+                stack.push(element);
+            }
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = unescape(&rest[..end]).ok_or_else(|| error("invalid entity", position))?;
+            stack.last_mut().unwrap().children.push(Node::Text(text));
+            position += end;
+        }
+    }
+
+    let document = stack.pop().unwrap();
+    if !stack.is_empty() {
+        return Err(error("unclosed element", xml.len()));
+    }
+    Ok(document)
+}
+
+/// Parse the start tag at `start`, returning the element, the end of the tag
+/// and whether the element is empty.
+fn parse_start_tag(xml: &str, start: usize) -> Result<(Element, usize, bool), ParseXmpError> {
+    let error = |reason, position| ParseXmpError { reason, position };
+    let is_name_char = |c: char| !c.is_whitespace() && !matches!(c, '/' | '>' | '=' | '<');
+
+    let mut position = start + 1;
+    let name_len = xml[position..]
+        .find(|c| !is_name_char(c))
+        .unwrap_or(xml.len() - position);
+    if name_len == 0 {
+        return Err(error("invalid tag name", position));
+    }
+    let name = xml[position..position + name_len].to_owned();
+    position += name_len;
+
+    let mut attributes = Vec::new();
+    loop {
+        position += xml[position..].len() - xml[position..].trim_start().len();
+        let rest = &xml[position..];
+        if rest.starts_with("/>") || rest.starts_with('>') {
+            let empty = rest.starts_with("/>");
+            let end = position + if empty { 2 } else { 1 };
+            let element = Element {
+                name,
+                attributes,
+                children: Vec::new(),
+                span: start..end,
+            };
+            return Ok((element, end, empty));
+        }
+
+        let name_len = rest.find(|c| !is_name_char(c)).unwrap_or(rest.len());
+        if name_len == 0 {
+            return Err(error("invalid attribute", position));
+        }
+        let attribute = rest[..name_len].to_owned();
+        let rest = rest[name_len..].trim_start();
+        let rest = rest
+            .strip_prefix('=')
+            .map(str::trim_start)
+            .ok_or_else(|| error("missing attribute value", position))?;
+        let quote = rest
+            .chars()
+            .next()
+            .filter(|c| matches!(c, '"' | '\''))
+            .ok_or_else(|| error("unquoted attribute value", position))?;
+        let value_len = rest[1..]
+            .find(quote)
+            .ok_or_else(|| error("unterminated attribute value", position))?;
+        let value =
+            unescape(&rest[1..1 + value_len]).ok_or_else(|| error("invalid entity", position))?;
+        attributes.push((attribute, value));
+        position = xml.len() - rest.len() + 1 + value_len + 1;
+    }
+}
+
+fn unescape(text: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        let end = rest[start..].find(';')? + start;
+        let entity = &rest[start + 1..end];
+        let c = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = match entity.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => entity.strip_prefix('#')?.parse().ok()?,
+                };
+                char::from_u32(code)?
+            }
+        };
+        unescaped.push(c);
+        rest = &rest[end + 1..];
+    }
+    unescaped.push_str(rest);
+    Some(unescaped)
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl MagickWand<'_> {
+    /// Parse the `XMP` profile of the image, see [Xmp].
+    ///
+    /// # Return
+    ///
+    /// `None` if the image has no XMP profile, an error if the wand has no
+    /// image.
+    pub fn get_xmp(&mut self) -> crate::Result<Option<Xmp>> {
+        self.check_has_images()?;
+        match self.get_image_profile_bytes(PROFILE_NAME) {
+            Some(profile) => Ok(Some(Xmp::parse(&profile)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Replace the `XMP` profile of the image by `xmp`.
+    pub fn set_xmp(&mut self, xmp: &Xmp) -> crate::Result<&mut Self> {
+        self.set_image_profile_bytes(PROFILE_NAME, &xmp.to_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::new_logo_magick_wand;
+
+    const PACKET: &str = r#"<?xpacket begin="" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/" x:xmptk="XMP Core 5.5.0">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about=""
+    xmlns:dc="http://purl.org/dc/elements/1.1/"
+    xmlns:xmp="http://ns.adobe.com/xap/1.0/"
+    xmlns:Iptc4xmpCore="http://iptc.org/std/Iptc4xmpCore/1.0/xmlns/"
+    xmp:CreatorTool="Camera &amp; Co"
+    xmp:Rating="4">
+   <dc:title>
+    <rdf:Alt>
+     <rdf:li xml:lang="x-default">Harbour</rdf:li>
+     <rdf:li xml:lang="fr-FR">Port</rdf:li>
+    </rdf:Alt>
+   </dc:title>
+   <dc:creator>
+    <rdf:Seq>
+     <rdf:li>Jane Doe</rdf:li>
+    </rdf:Seq>
+   </dc:creator>
+   <dc:subject>
+    <rdf:Bag>
+     <rdf:li>sunset</rdf:li>
+     <rdf:li>harbour</rdf:li>
+    </rdf:Bag>
+   </dc:subject>
+   <!-- A structure. -->
+   <Iptc4xmpCore:CreatorContactInfo rdf:parseType="Resource">
+    <Iptc4xmpCore:CiEmailWork>jane@example.com</Iptc4xmpCore:CiEmailWork>
+   </Iptc4xmpCore:CreatorContactInfo>
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"#;
+
+    #[test]
+    fn test_xmp_parse() {
+        let xmp = Xmp::parse(PACKET.as_bytes()).unwrap();
+        assert_eq!(xmp.title(), Some("Harbour"));
+        assert_eq!(
+            xmp.get(ns::DC, "title"),
+            Some(&XmpValue::Alt(vec![
+                ("x-default".to_owned(), "Harbour".to_owned()),
+                ("fr-FR".to_owned(), "Port".to_owned()),
+            ]))
+        );
+        assert_eq!(xmp.creators(), ["Jane Doe"]);
+        assert_eq!(xmp.subjects(), ["sunset", "harbour"]);
+        assert_eq!(
+            xmp.get(ns::XMP, "CreatorTool"),
+            Some(&XmpValue::Text("Camera & Co".to_owned()))
+        );
+        assert!(matches!(
+            xmp.get(ns::IPTC_CORE, "CreatorContactInfo"),
+            Some(XmpValue::Raw(raw)) if raw.contains("jane@example.com")
+        ));
+        assert_eq!(xmp.iter().count(), 6);
+    }
+
+    #[test]
+    fn test_xmp_round_trip() {
+        let mut xmp = Xmp::parse(PACKET.as_bytes()).unwrap();
+        xmp.set_title("Le port");
+        xmp.set_rights("© 2024 <Jane> & \"Doe\"");
+        xmp.set_creators(["Jane Doe", "John Doe"]);
+        xmp.set(
+            "http://example.com/ns/",
+            "Custom",
+            XmpValue::Text("value".to_owned()),
+        );
+        xmp.remove(ns::XMP, "Rating");
+
+        let parsed = Xmp::parse(&xmp.to_bytes()).unwrap();
+        assert_eq!(parsed.properties, xmp.properties);
+        assert_eq!(parsed.title(), Some("Le port"));
+        assert_eq!(
+            parsed.get(ns::DC, "title").unwrap(),
+            &XmpValue::Alt(vec![
+                ("x-default".to_owned(), "Le port".to_owned()),
+                ("fr-FR".to_owned(), "Port".to_owned()),
+            ])
+        );
+        assert_eq!(parsed.rights(), Some("© 2024 <Jane> & \"Doe\""));
+        assert_eq!(parsed.creators(), ["Jane Doe", "John Doe"]);
+        assert_eq!(parsed.get(ns::XMP, "Rating"), None);
+    }
+
+    #[test]
+    fn test_xmp_parse_invalid() {
+        assert_eq!(
+            Xmp::parse(b"<x:xmpmeta><rdf:RDF></x:xmpmeta>")
+                .unwrap_err()
+                .get_reason(),
+            "mismatched end tag"
+        );
+        assert!(Xmp::parse(b"<x:xmpmeta>").is_err());
+        assert!(Xmp::parse(b"<a b=c/>").is_err());
+        assert!(Xmp::parse(b"<a>&unknown;</a>").is_err());
+        assert!(Xmp::parse(b"\xff").is_err());
+        assert_eq!(Xmp::parse(b"").unwrap(), Xmp::new());
+    }
+
+    #[test]
+    fn test_xmp_jpeg_round_trip() {
+        crate::initialize();
+        assert!(MagickWand::new().get_xmp().is_err());
+
+        let mut mw = new_logo_magick_wand();
+        assert_eq!(mw.get_xmp().unwrap(), None);
+
+        let mut xmp = Xmp::new();
+        xmp.set_title("Logo");
+        xmp.set_creators(["jmjoy"]);
+        xmp.set_rights("MIT");
+        xmp.set_usage_terms("Free to use with attribution");
+        mw.set_xmp(&xmp).unwrap();
+        mw.set_image_format("JPEG").unwrap();
+        let blob = mw.write_image_blob().unwrap();
+
+        let mut mw = MagickWand::new();
+        mw.read_image_blob(&blob).unwrap();
+        let parsed = mw.get_xmp().unwrap().unwrap();
+        assert_eq!(parsed.properties, xmp.properties);
+        assert_eq!(parsed.title(), Some("Logo"));
+        assert_eq!(parsed.creators(), ["jmjoy"]);
+        assert_eq!(parsed.rights(), Some("MIT"));
+        assert_eq!(parsed.usage_terms(), Some("Free to use with attribution"));
+    }
+}